@@ -0,0 +1,836 @@
+use crate::edit_guard::CommitMode;
+use crate::error::error_for_status;
+use crate::oauth2::Oauth2TokenManager;
+use crate::retry::{RetryPolicy, SendWithRetry};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+
+mod resumable;
+
+pub const ANDROID_PUBLISHER_SCOPE: &str = "https://www.googleapis.com/auth/androidpublisher";
+pub const DEFAULT_SERVICE_ENDPOINT: &str = "https://androidpublisher.googleapis.com";
+
+pub struct ApiClient {
+    client: Client,
+    package_name: String,
+    token_manager: Oauth2TokenManager,
+    service_endpoint: Url,
+    retry_policy: RetryPolicy,
+    commit_mode: CommitMode,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppEdit {
+    pub id: String,
+    pub expiry_time_seconds: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle {
+    pub version_code: i64,
+    pub sha1: String,
+    pub sha256: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Apk {
+    pub version_code: i64,
+    pub binary: ApkBinary,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApkBinary {
+    pub sha1: String,
+    pub sha256: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackList {
+    kind: String,
+    #[serde(default)]
+    pub tracks: Vec<Track>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Track {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub releases: Option<Vec<Release>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Release {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_codes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ReleaseStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_fraction: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_notes: Option<Vec<LocalizedText>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_app_update_priority: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_targeting: Option<CountryTargeting>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CountryTargeting {
+    #[serde(default)]
+    pub countries: Vec<String>,
+    #[serde(default)]
+    pub include_rest_of_world: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedText {
+    pub language: String,
+    pub text: String,
+}
+
+/// Who can access a closed testing track.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Testers {
+    #[serde(default)]
+    pub google_groups: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingList {
+    kind: String,
+    #[serde(default)]
+    pub listings: Vec<Listing>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Listing {
+    pub language: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_website: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_phone: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeobfuscationFilesUploadResponse {
+    pub deobfuscation_file: DeobfuscationFile,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeobfuscationFile {
+    pub symbol_type: DeobfuscationFileType,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DeobfuscationFileType {
+    Proguard,
+    NativeCode,
+}
+
+impl std::fmt::Display for DeobfuscationFileType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Proguard => "proguard",
+            Self::NativeCode => "nativeCode",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpansionFilesUploadResponse {
+    pub expansion_file: ExpansionFile,
+}
+
+/// An APK expansion (OBB) file, either uploaded for the APK or referenced from another version.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpansionFile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub references_version: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "camelCase")]
+pub enum ExpansionFileType {
+    Main,
+    Patch,
+}
+
+impl std::fmt::Display for ExpansionFileType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Main => "main",
+            Self::Patch => "patch",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageList {
+    #[serde(default)]
+    pub images: Vec<Image>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageUpload {
+    pub image: Image,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImagesDeleteAll {
+    #[serde(default)]
+    pub deleted: Vec<Image>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Image {
+    pub id: String,
+    pub url: String,
+    pub sha1: String,
+    pub sha256: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+#[serde(rename_all = "camelCase")]
+#[value(rename_all = "camelCase")]
+pub enum ImageType {
+    PhoneScreenshots,
+    SevenInchScreenshots,
+    TenInchScreenshots,
+    TvScreenshots,
+    WearScreenshots,
+    Icon,
+    FeatureGraphic,
+    TvBanner,
+}
+
+impl ImageType {
+    pub const ALL: [ImageType; 8] = [
+        Self::PhoneScreenshots,
+        Self::SevenInchScreenshots,
+        Self::TenInchScreenshots,
+        Self::TvScreenshots,
+        Self::WearScreenshots,
+        Self::Icon,
+        Self::FeatureGraphic,
+        Self::TvBanner,
+    ];
+
+    /// Whether a store listing holds a single image of this type rather than a list.
+    pub fn is_single(self) -> bool {
+        matches!(self, Self::Icon | Self::FeatureGraphic | Self::TvBanner)
+    }
+}
+
+impl std::fmt::Display for ImageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::PhoneScreenshots => "phoneScreenshots",
+            Self::SevenInchScreenshots => "sevenInchScreenshots",
+            Self::TenInchScreenshots => "tenInchScreenshots",
+            Self::TvScreenshots => "tvScreenshots",
+            Self::WearScreenshots => "wearScreenshots",
+            Self::Icon => "icon",
+            Self::FeatureGraphic => "featureGraphic",
+            Self::TvBanner => "tvBanner",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "camelCase")]
+pub enum ReleaseStatus {
+    #[value(skip)]
+    StatusUnspecified,
+    Draft,
+    #[value(name = "inProgress", alias = "in-progress")]
+    InProgress,
+    Halted,
+    Completed,
+}
+
+impl std::fmt::Display for ReleaseStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::StatusUnspecified => "statusUnspecified",
+            Self::Draft => "draft",
+            Self::InProgress => "inProgress",
+            Self::Halted => "halted",
+            Self::Completed => "completed",
+        })
+    }
+}
+
+impl Release {
+    /// The highest version code in this release, used to find the newest release of a track.
+    pub fn max_version_code(&self) -> Option<i64> {
+        self.version_codes
+            .iter()
+            .flatten()
+            .filter_map(|version_code| version_code.parse().ok())
+            .max()
+    }
+
+    pub fn validate(&self) -> eyre::Result<()> {
+        if let Some(user_fraction) = self.user_fraction {
+            if !matches!(
+                self.status,
+                Some(ReleaseStatus::InProgress) | Some(ReleaseStatus::Halted)
+            ) {
+                eyre::bail!("a user fraction can only be set for inProgress or halted releases");
+            }
+            if !(user_fraction > 0.0 && user_fraction < 1.0) {
+                eyre::bail!("user fraction must be between 0 and 1 exclusive, got {user_fraction}");
+            }
+        } else if self.status == Some(ReleaseStatus::InProgress) {
+            eyre::bail!("an inProgress release requires a user fraction");
+        }
+        Ok(())
+    }
+}
+
+impl ApiClient {
+    pub fn new(
+        package_name: String,
+        token_manager: Oauth2TokenManager,
+        service_endpoint: Url,
+    ) -> Self {
+        Self {
+            client: Client::new(),
+            package_name,
+            token_manager,
+            service_endpoint,
+            retry_policy: RetryPolicy::default(),
+            commit_mode: CommitMode::default(),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_commit_mode(mut self, commit_mode: CommitMode) -> Self {
+        self.commit_mode = commit_mode;
+        self
+    }
+
+    pub fn commit_mode(&self) -> CommitMode {
+        self.commit_mode
+    }
+
+    fn url(&self, path: impl AsRef<str>) -> Url {
+        let mut url = self.service_endpoint.clone();
+        url.set_path(path.as_ref());
+        url
+    }
+
+    pub async fn create_edit(&self) -> eyre::Result<AppEdit> {
+        let res = self
+            .client
+            .post(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits",
+                self.package_name
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .json(&serde_json::Value::Object(Default::default()))
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn get_edit(&self, edit_id: &str) -> eyre::Result<AppEdit> {
+        let res = self
+            .client
+            .get(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}",
+                self.package_name, edit_id
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn commit_edit(&self, edit_id: &str) -> eyre::Result<AppEdit> {
+        let res = self
+            .client
+            .post(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}:commit",
+                self.package_name, edit_id
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .header("Content-Length", "0")
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn validate_edit(&self, edit_id: &str) -> eyre::Result<AppEdit> {
+        let res = self
+            .client
+            .post(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}:validate",
+                self.package_name, edit_id
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .header("Content-Length", "0")
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn delete_edit(&self, edit_id: &str) -> eyre::Result<()> {
+        let res = self
+            .client
+            .delete(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}",
+                self.package_name, edit_id
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        error_for_status(res).await?;
+        Ok(())
+    }
+
+    pub async fn upload_bundle(
+        &self,
+        edit_id: &str,
+        bundle: tokio::fs::File,
+    ) -> eyre::Result<Bundle> {
+        self.resumable_upload(
+            format!(
+                "/upload/androidpublisher/v3/applications/{}/edits/{}/bundles",
+                self.package_name, edit_id,
+            ),
+            "application/octet-stream",
+            bundle,
+        )
+        .await
+    }
+
+    pub async fn upload_apk(&self, edit_id: &str, apk: tokio::fs::File) -> eyre::Result<Apk> {
+        self.resumable_upload(
+            format!(
+                "/upload/androidpublisher/v3/applications/{}/edits/{}/apks",
+                self.package_name, edit_id,
+            ),
+            "application/vnd.android.package-archive",
+            apk,
+        )
+        .await
+    }
+
+    pub async fn list_tracks(&self, edit_id: &str) -> eyre::Result<TrackList> {
+        let res = self
+            .client
+            .get(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/tracks",
+                self.package_name, edit_id
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn upload_deobfuscation_file(
+        &self,
+        edit_id: &str,
+        version_code: i64,
+        file_type: DeobfuscationFileType,
+        file: tokio::fs::File,
+    ) -> eyre::Result<DeobfuscationFilesUploadResponse> {
+        self.resumable_upload(
+            format!(
+                "/upload/androidpublisher/v3/applications/{}/edits/{}/apks/{}/deobfuscationFiles/{}",
+                self.package_name, edit_id, version_code, file_type,
+            ),
+            "application/octet-stream",
+            file,
+        )
+        .await
+    }
+
+    pub async fn upload_expansion_file(
+        &self,
+        edit_id: &str,
+        version_code: i64,
+        file_type: ExpansionFileType,
+        file: tokio::fs::File,
+    ) -> eyre::Result<ExpansionFilesUploadResponse> {
+        self.resumable_upload(
+            format!(
+                "/upload/androidpublisher/v3/applications/{}/edits/{}/apks/{}/expansionFiles/{}",
+                self.package_name, edit_id, version_code, file_type,
+            ),
+            "application/octet-stream",
+            file,
+        )
+        .await
+    }
+
+    pub async fn get_expansion_file(
+        &self,
+        edit_id: &str,
+        version_code: i64,
+        file_type: ExpansionFileType,
+    ) -> eyre::Result<ExpansionFile> {
+        let res = self
+            .client
+            .get(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/apks/{}/expansionFiles/{}",
+                self.package_name, edit_id, version_code, file_type
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn update_expansion_file(
+        &self,
+        edit_id: &str,
+        version_code: i64,
+        file_type: ExpansionFileType,
+        expansion_file: &ExpansionFile,
+    ) -> eyre::Result<ExpansionFile> {
+        let res = self
+            .client
+            .put(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/apks/{}/expansionFiles/{}",
+                self.package_name, edit_id, version_code, file_type
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .json(expansion_file)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    /// Changes only the fields set in `expansion_file`.
+    pub async fn patch_expansion_file(
+        &self,
+        edit_id: &str,
+        version_code: i64,
+        file_type: ExpansionFileType,
+        expansion_file: &ExpansionFile,
+    ) -> eyre::Result<ExpansionFile> {
+        let res = self
+            .client
+            .patch(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/apks/{}/expansionFiles/{}",
+                self.package_name, edit_id, version_code, file_type
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .json(expansion_file)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn get_track(&self, edit_id: &str, track: &str) -> eyre::Result<Track> {
+        let res = self
+            .client
+            .get(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/tracks/{}",
+                self.package_name, edit_id, track
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn update_track(
+        &self,
+        edit_id: &str,
+        track: &str,
+        releases: Vec<Release>,
+    ) -> eyre::Result<Track> {
+        let res = self
+            .client
+            .put(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/tracks/{}",
+                self.package_name, edit_id, track
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .json(&Track {
+                releases: Some(releases),
+                ..Default::default()
+            })
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn list_listings(&self, edit_id: &str) -> eyre::Result<ListingList> {
+        let res = self
+            .client
+            .get(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/listings",
+                self.package_name, edit_id
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn get_listing(&self, edit_id: &str, language: &str) -> eyre::Result<Listing> {
+        let res = self
+            .client
+            .get(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/listings/{}",
+                self.package_name, edit_id, language
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn update_listing(&self, edit_id: &str, listing: &Listing) -> eyre::Result<Listing> {
+        let res = self
+            .client
+            .put(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/listings/{}",
+                self.package_name, edit_id, listing.language
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .json(listing)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn delete_listing(&self, edit_id: &str, language: &str) -> eyre::Result<()> {
+        let res = self
+            .client
+            .delete(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/listings/{}",
+                self.package_name, edit_id, language
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        error_for_status(res).await?;
+        Ok(())
+    }
+
+    pub async fn list_images(
+        &self,
+        edit_id: &str,
+        language: &str,
+        image_type: ImageType,
+    ) -> eyre::Result<ImageList> {
+        let res = self
+            .client
+            .get(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/listings/{}/{}",
+                self.package_name, edit_id, language, image_type
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn upload_image(
+        &self,
+        edit_id: &str,
+        language: &str,
+        image_type: ImageType,
+        content_type: &str,
+        image: Vec<u8>,
+    ) -> eyre::Result<ImageUpload> {
+        let res = self
+            .client
+            .post(self.url(format!(
+                "/upload/androidpublisher/v3/applications/{}/edits/{}/listings/{}/{}",
+                self.package_name, edit_id, language, image_type
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .header("Content-type", content_type)
+            .body(image)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn delete_image(
+        &self,
+        edit_id: &str,
+        language: &str,
+        image_type: ImageType,
+        image_id: &str,
+    ) -> eyre::Result<()> {
+        let res = self
+            .client
+            .delete(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/listings/{}/{}/{}",
+                self.package_name, edit_id, language, image_type, image_id
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        error_for_status(res).await?;
+        Ok(())
+    }
+
+    pub async fn delete_all_images(
+        &self,
+        edit_id: &str,
+        language: &str,
+        image_type: ImageType,
+    ) -> eyre::Result<ImagesDeleteAll> {
+        let res = self
+            .client
+            .delete(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/listings/{}/{}",
+                self.package_name, edit_id, language, image_type
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn get_details(&self, edit_id: &str) -> eyre::Result<AppDetails> {
+        let res = self
+            .client
+            .get(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/details",
+                self.package_name, edit_id
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn update_details(
+        &self,
+        edit_id: &str,
+        details: &AppDetails,
+    ) -> eyre::Result<AppDetails> {
+        let res = self
+            .client
+            .put(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/details",
+                self.package_name, edit_id
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .json(details)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    /// Downloads the full-size image behind an image's URL.
+    pub async fn download_image(&self, image: &Image) -> eyre::Result<Vec<u8>> {
+        // without a size parameter the URL serves a scaled down copy
+        let res = self
+            .client
+            .get(format!("{}=s0", image.url))
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.bytes().await?.to_vec())
+    }
+
+    pub async fn get_testers(&self, edit_id: &str, track: &str) -> eyre::Result<Testers> {
+        let res = self
+            .client
+            .get(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/testers/{}",
+                self.package_name, edit_id, track
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn update_testers(
+        &self,
+        edit_id: &str,
+        track: &str,
+        testers: &Testers,
+    ) -> eyre::Result<Testers> {
+        let res = self
+            .client
+            .put(self.url(format!(
+                "/androidpublisher/v3/applications/{}/edits/{}/testers/{}",
+                self.package_name, edit_id, track
+            )))
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .json(testers)
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        Ok(res.json().await?)
+    }
+}
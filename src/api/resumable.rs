@@ -0,0 +1,215 @@
+use super::ApiClient;
+use crate::error::{error_for_status, ApiError};
+use crate::retry::{is_transient_status, SendWithRetry};
+use indicatif::ProgressBar;
+use reqwest::header::HeaderMap;
+use reqwest::{Body, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+/// Upload chunk size, must be a multiple of 256 KiB.
+const CHUNK_SIZE: u64 = 32 * 256 * 1024;
+/// Granularity at which the progress bar advances within a chunk.
+const PROGRESS_PIECE_SIZE: usize = 64 * 1024;
+
+const RESUME_INCOMPLETE: u16 = 308;
+
+/// Why a chunk has to be sent again.
+enum ChunkFailure {
+    Status(Response),
+    Request(reqwest::Error),
+    Stalled(u64),
+}
+
+impl std::fmt::Display for ChunkFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Status(res) => write!(f, "{}", res.status()),
+            Self::Request(err) => write!(f, "{err}"),
+            Self::Stalled(offset) => write!(f, "no bytes past offset {offset} were committed"),
+        }
+    }
+}
+
+enum UploadStatus {
+    Incomplete(u64),
+    Complete(Response),
+}
+
+impl ApiClient {
+    /// Uploads `file` to `path` using Google's resumable upload protocol, sending it in chunks
+    /// and picking up from the last committed byte when a chunk fails transiently.
+    pub(super) async fn resumable_upload<T: DeserializeOwned>(
+        &self,
+        path: String,
+        content_type: &str,
+        mut file: tokio::fs::File,
+    ) -> eyre::Result<T> {
+        let total_size = file.metadata().await?.len();
+        if total_size == 0 {
+            eyre::bail!("refusing to upload an empty file");
+        }
+        let session = self
+            .start_resumable_upload(path, content_type, total_size)
+            .await?;
+        let bar = ProgressBar::new(total_size);
+        let mut offset = 0;
+        let mut failures = 0;
+
+        loop {
+            bar.set_position(offset);
+            file.seek(std::io::SeekFrom::Start(offset)).await?;
+            let mut chunk = Vec::with_capacity(CHUNK_SIZE.min(total_size - offset) as usize);
+            (&mut file).take(CHUNK_SIZE).read_to_end(&mut chunk).await?;
+            let chunk_size = chunk.len() as u64;
+            let end = offset + chunk_size - 1;
+
+            let result = self
+                .client
+                .put(session.clone())
+                .bearer_auth(self.token_manager.token().await?.access_token)
+                .header("Content-Length", chunk_size)
+                .header(
+                    "Content-Range",
+                    format!("bytes {offset}-{end}/{total_size}"),
+                )
+                .body(progress_body(chunk, offset, bar.clone()))
+                .send()
+                .await;
+            let failure = match result {
+                Ok(res) if res.status().as_u16() == RESUME_INCOMPLETE => {
+                    let committed = committed_offset(res.headers());
+                    if committed > offset {
+                        offset = committed;
+                        failures = 0;
+                        continue;
+                    }
+                    // the server kept none of the chunk, retrying it forever would never end
+                    ChunkFailure::Stalled(offset)
+                }
+                Ok(res) if res.status().is_success() => {
+                    bar.finish();
+                    return Ok(res.json().await?);
+                }
+                Ok(res) if is_transient_status(res.status()) => ChunkFailure::Status(res),
+                // a connection dropped mid-chunk surfaces as a request error, ask the
+                // server what it kept rather than giving up on the whole upload
+                Err(err) if !err.is_builder() => ChunkFailure::Request(err),
+                Ok(res) => return Err(ApiError::from_response(res).await.into()),
+                Err(err) => return Err(err.into()),
+            };
+
+            failures += 1;
+            if failures > self.retry_policy.max_retries {
+                // keep the typed error so that the exit code still tells rate limits apart
+                let err = match failure {
+                    ChunkFailure::Status(res) => ApiError::from_response(res).await.into(),
+                    ChunkFailure::Request(err) => err.into(),
+                    failure => eyre::eyre!("{failure}"),
+                };
+                return Err(err.wrap_err(format!("upload failed after {} retries", failures - 1)));
+            }
+            let delay = self.retry_policy.backoff(failures - 1);
+            bar.suspend(|| eprintln!("upload chunk failed: {failure}, resuming in {delay:?}"));
+            tokio::time::sleep(delay).await;
+            match self.query_upload_status(&session, total_size).await? {
+                UploadStatus::Incomplete(committed) => offset = committed,
+                UploadStatus::Complete(res) => {
+                    bar.finish();
+                    return Ok(res.json().await?);
+                }
+            }
+        }
+    }
+
+    async fn start_resumable_upload(
+        &self,
+        path: String,
+        content_type: &str,
+        total_size: u64,
+    ) -> eyre::Result<Url> {
+        let mut url = self.url(path);
+        url.query_pairs_mut().append_pair("uploadType", "resumable");
+        let res = self
+            .client
+            .post(url)
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .header("X-Upload-Content-Type", content_type)
+            .header("X-Upload-Content-Length", total_size)
+            .header("Content-Length", "0")
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        let res = error_for_status(res).await?;
+        let location = res
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .ok_or_else(|| eyre::eyre!("resumable upload response is missing a session URI"))?;
+        Ok(location.to_str()?.parse()?)
+    }
+
+    async fn query_upload_status(
+        &self,
+        session: &Url,
+        total_size: u64,
+    ) -> eyre::Result<UploadStatus> {
+        let res = self
+            .client
+            .put(session.clone())
+            .bearer_auth(self.token_manager.token().await?.access_token)
+            .header("Content-Range", format!("bytes */{total_size}"))
+            .header("Content-Length", "0")
+            .send_with_retry(&self.retry_policy)
+            .await?;
+        match res.status() {
+            status if status.as_u16() == RESUME_INCOMPLETE => {
+                Ok(UploadStatus::Incomplete(committed_offset(res.headers())))
+            }
+            StatusCode::OK | StatusCode::CREATED => Ok(UploadStatus::Complete(res)),
+            _ => Err(ApiError::from_response(res).await.into()),
+        }
+    }
+}
+
+/// Reads the number of bytes the server has persisted from a `Range: bytes=0-<last>` header.
+fn committed_offset(headers: &HeaderMap) -> u64 {
+    headers
+        .get(reqwest::header::RANGE)
+        .and_then(|range| range.to_str().ok())
+        .and_then(|range| range.rsplit_once('-'))
+        .and_then(|(_, last)| last.trim().parse::<u64>().ok())
+        .map_or(0, |last| last + 1)
+}
+
+fn progress_body(chunk: Vec<u8>, offset: u64, bar: ProgressBar) -> Body {
+    let pieces: Vec<Vec<u8>> = chunk
+        .chunks(PROGRESS_PIECE_SIZE)
+        .map(<[u8]>::to_vec)
+        .collect();
+    let async_stream = async_stream::stream! {
+        let mut sent = offset;
+        for piece in pieces {
+            sent += piece.len() as u64;
+            bar.set_position(sent);
+            yield Ok::<_, std::io::Error>(piece);
+        }
+    };
+    Body::wrap_stream(async_stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderValue, RANGE};
+
+    #[test]
+    fn committed_offset_follows_range_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RANGE, HeaderValue::from_static("bytes=0-8388607"));
+        assert_eq!(committed_offset(&headers), 8 * 1024 * 1024);
+    }
+
+    #[test]
+    fn committed_offset_without_range_header_is_zero() {
+        assert_eq!(committed_offset(&HeaderMap::new()), 0);
+    }
+}
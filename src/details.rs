@@ -0,0 +1,159 @@
+use crate::api::{ApiClient, AppDetails};
+use crate::edit_guard::EditGuard;
+use crate::output::{self, cell, Committed, OutputFormat, Tabular};
+use clap::Subcommand;
+use std::path::{Path, PathBuf};
+
+#[derive(Subcommand, Debug)]
+pub enum DetailsCommand {
+    /// Show the default language and contact details
+    Get,
+    /// Change the default language or contact details
+    Update {
+        /// JSON file with defaultLanguage, contactEmail, contactPhone and contactWebsite
+        #[arg(long)]
+        file: Option<PathBuf>,
+        #[arg(long)]
+        default_language: Option<String>,
+        #[arg(long)]
+        contact_email: Option<String>,
+        #[arg(long)]
+        contact_phone: Option<String>,
+        #[arg(long)]
+        contact_website: Option<String>,
+    },
+}
+
+pub async fn run(
+    client: &ApiClient,
+    command: DetailsCommand,
+    format: OutputFormat,
+) -> eyre::Result<()> {
+    match command {
+        DetailsCommand::Get => {
+            let edit = EditGuard::open(client).await?;
+            let details = edit.run(client.get_details(edit.id())).await?;
+            edit.discard().await?;
+            output::print(format, &details)
+        }
+        DetailsCommand::Update {
+            file,
+            default_language,
+            contact_email,
+            contact_phone,
+            contact_website,
+        } => {
+            let from_file = match file {
+                Some(file) => read_file(&file)?,
+                None => AppDetails::default(),
+            };
+            let desired = merge(
+                &from_file,
+                &AppDetails {
+                    default_language,
+                    contact_website,
+                    contact_email,
+                    contact_phone,
+                },
+            );
+            let edit = EditGuard::open(client).await?;
+            let details = edit
+                .run(async {
+                    let current = client.get_details(edit.id()).await?;
+                    client
+                        .update_details(edit.id(), &merge(&current, &desired))
+                        .await
+                })
+                .await?;
+            let committed = edit.commit().await?.is_committed();
+            output::print(
+                format,
+                &Committed {
+                    committed,
+                    result: details,
+                },
+            )
+        }
+    }
+}
+
+pub fn read_file(path: &Path) -> eyre::Result<AppDetails> {
+    let file = std::fs::File::open(path)
+        .map_err(|err| eyre::eyre!("failed to read {}: {}", path.display(), err))?;
+    Ok(serde_json::from_reader(file)?)
+}
+
+/// Overlays the fields set in `desired` onto `current`.
+pub fn merge(current: &AppDetails, desired: &AppDetails) -> AppDetails {
+    AppDetails {
+        default_language: desired
+            .default_language
+            .clone()
+            .or_else(|| current.default_language.clone()),
+        contact_website: desired
+            .contact_website
+            .clone()
+            .or_else(|| current.contact_website.clone()),
+        contact_email: desired
+            .contact_email
+            .clone()
+            .or_else(|| current.contact_email.clone()),
+        contact_phone: desired
+            .contact_phone
+            .clone()
+            .or_else(|| current.contact_phone.clone()),
+    }
+}
+
+/// Lists the fields that differ between `current` and `desired` with their old and new values.
+pub fn diff(
+    current: &AppDetails,
+    desired: &AppDetails,
+) -> Vec<(&'static str, Option<String>, Option<String>)> {
+    let fields = [
+        (
+            "defaultLanguage",
+            current.default_language.clone(),
+            desired.default_language.clone(),
+        ),
+        (
+            "contactEmail",
+            current.contact_email.clone(),
+            desired.contact_email.clone(),
+        ),
+        (
+            "contactPhone",
+            current.contact_phone.clone(),
+            desired.contact_phone.clone(),
+        ),
+        (
+            "contactWebsite",
+            current.contact_website.clone(),
+            desired.contact_website.clone(),
+        ),
+    ];
+    fields
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .collect()
+}
+
+impl Tabular for AppDetails {
+    fn headers(&self) -> &'static [&'static str] {
+        &[
+            "DEFAULT LANGUAGE",
+            "CONTACT EMAIL",
+            "CONTACT PHONE",
+            "CONTACT WEBSITE",
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            cell(self.default_language.as_ref()),
+            cell(self.contact_email.as_ref()),
+            cell(self.contact_phone.as_ref()),
+            cell(self.contact_website.as_ref()),
+        ]]
+    }
+}
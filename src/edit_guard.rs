@@ -0,0 +1,129 @@
+use crate::api::{ApiClient, AppEdit};
+use crate::plan;
+use std::future::Future;
+
+/// What happens when a command commits its edit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommitMode {
+    #[default]
+    Commit,
+    /// Print the changes the edit would make and delete it instead of committing
+    Plan,
+    /// Print the changes and ask before committing
+    Confirm,
+}
+
+/// How [`EditGuard::commit`] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub enum CommitOutcome {
+    Committed,
+    /// The changes were printed and the edit deleted without committing it
+    Planned,
+}
+
+impl CommitOutcome {
+    pub fn is_committed(self) -> bool {
+        self == Self::Committed
+    }
+}
+
+/// An open edit that is deleted again if the work done in it fails or is interrupted,
+/// so that aborted runs don't leave conflicting edits behind.
+pub struct EditGuard<'a> {
+    client: &'a ApiClient,
+    edit: AppEdit,
+    /// Whether the edit was opened here, only those are deleted again
+    owned: bool,
+}
+
+impl<'a> EditGuard<'a> {
+    pub async fn open(client: &'a ApiClient) -> eyre::Result<EditGuard<'a>> {
+        let edit = client.create_edit().await?;
+        Ok(Self {
+            client,
+            edit,
+            owned: true,
+        })
+    }
+
+    /// Wraps an edit opened elsewhere, such as with `edits create`, so that committing it honours
+    /// `--plan` and `--confirm`. The edit is left open whenever it isn't committed.
+    pub fn attach(client: &'a ApiClient, edit: AppEdit) -> EditGuard<'a> {
+        Self {
+            client,
+            edit,
+            owned: false,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.edit.id
+    }
+
+    /// Runs `work` against the edit, deleting the edit if it fails or Ctrl-C is pressed.
+    pub async fn run<T>(&self, work: impl Future<Output = eyre::Result<T>>) -> eyre::Result<T> {
+        let result = interruptible(work).await;
+        if result.is_err() {
+            self.delete().await;
+        }
+        result
+    }
+
+    pub async fn commit(self) -> eyre::Result<CommitOutcome> {
+        match self.client.commit_mode() {
+            CommitMode::Commit => {}
+            CommitMode::Plan => {
+                self.run(plan::print_diff(self.client, self.id())).await?;
+                self.delete().await;
+                eprintln!("edit {} was not committed", self.edit.id);
+                return Ok(CommitOutcome::Planned);
+            }
+            CommitMode::Confirm => {
+                self.run(plan::print_diff(self.client, self.id())).await?;
+                let confirmed = interruptible(plan::confirm()).await;
+                if !matches!(confirmed, Ok(true)) {
+                    self.delete().await;
+                    confirmed?;
+                    eyre::bail!("aborted, edit {} was not committed", self.edit.id);
+                }
+            }
+        }
+        let result = interruptible(self.client.commit_edit(&self.edit.id)).await;
+        if result.is_err() {
+            self.delete().await;
+        }
+        result.map(|_| CommitOutcome::Committed)
+    }
+
+    /// Validates the edit without publishing it, then deletes it either way.
+    pub async fn validate(self) -> eyre::Result<AppEdit> {
+        let result = interruptible(self.client.validate_edit(&self.edit.id)).await;
+        self.delete().await;
+        result
+    }
+
+    /// Deletes the edit, for edits only opened to read the current state.
+    pub async fn discard(self) -> eyre::Result<()> {
+        interruptible(self.client.delete_edit(&self.edit.id)).await
+    }
+
+    async fn delete(&self) {
+        if !self.owned {
+            return;
+        }
+        if let Err(err) = self.client.delete_edit(&self.edit.id).await {
+            eprintln!("failed to delete edit {}: {}", self.edit.id, err);
+        }
+    }
+}
+
+/// Resolves `work`, failing instead when Ctrl-C is pressed. Listening for Ctrl-C replaces the
+/// default SIGINT handling for the rest of the process, so every wait on an open edit has to go
+/// through here to stay interruptible.
+async fn interruptible<T>(work: impl Future<Output = eyre::Result<T>>) -> eyre::Result<T> {
+    tokio::select! {
+        result = work => result,
+        _ = tokio::signal::ctrl_c() => Err(eyre::eyre!("interrupted")),
+    }
+}
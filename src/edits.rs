@@ -0,0 +1,83 @@
+use crate::api::{ApiClient, AppEdit};
+use crate::edit_guard::EditGuard;
+use crate::output::{self, Committed, OutputFormat, Tabular};
+use clap::Subcommand;
+use serde::Serialize;
+
+#[derive(Subcommand, Debug)]
+pub enum EditsCommand {
+    /// Open a new edit
+    Create,
+    /// Show an open edit
+    Get { edit_id: String },
+    /// Commit an open edit
+    Commit { edit_id: String },
+    /// Check an open edit for errors without committing it
+    Validate { edit_id: String },
+    /// Delete an open edit
+    Delete { edit_id: String },
+}
+
+pub async fn run(
+    client: &ApiClient,
+    command: EditsCommand,
+    format: OutputFormat,
+) -> eyre::Result<()> {
+    let edit = match command {
+        EditsCommand::Create => client.create_edit().await?,
+        EditsCommand::Get { edit_id } => client.get_edit(&edit_id).await?,
+        EditsCommand::Commit { edit_id } => {
+            let edit = client.get_edit(&edit_id).await?;
+            let committed = EditGuard::attach(client, edit.clone())
+                .commit()
+                .await?
+                .is_committed();
+            return output::print(
+                format,
+                &Committed {
+                    committed,
+                    result: edit,
+                },
+            );
+        }
+        EditsCommand::Validate { edit_id } => client.validate_edit(&edit_id).await?,
+        EditsCommand::Delete { edit_id } => {
+            client.delete_edit(&edit_id).await?;
+            return output::print(
+                format,
+                &DeletedEdit {
+                    edit_id,
+                    deleted: true,
+                },
+            );
+        }
+    };
+    output::print(format, &edit)
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedEdit {
+    edit_id: String,
+    deleted: bool,
+}
+
+impl Tabular for DeletedEdit {
+    fn headers(&self) -> &'static [&'static str] {
+        &["EDIT", "DELETED"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![self.edit_id.clone(), self.deleted.to_string()]]
+    }
+}
+
+impl Tabular for AppEdit {
+    fn headers(&self) -> &'static [&'static str] {
+        &["EDIT", "EXPIRY TIME SECONDS"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![self.id.clone(), self.expiry_time_seconds.clone()]]
+    }
+}
@@ -0,0 +1,190 @@
+use reqwest::{Response, StatusCode};
+use serde::Deserialize;
+use std::fmt;
+
+#[derive(Deserialize, Debug)]
+struct ErrorEnvelope {
+    error: ErrorBody,
+    error_description: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum ErrorBody {
+    Status(ErrorStatus),
+    // OAuth2 token endpoint errors, e.g. `{"error": "invalid_grant", "error_description": ".."}`
+    Code(String),
+}
+
+/// The `error` object of Google's JSON error envelope.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ErrorStatus {
+    pub code: u16,
+    pub message: String,
+    pub status: Option<String>,
+    #[serde(default)]
+    pub details: Vec<serde_json::Value>,
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    InvalidArgument(ErrorStatus),
+    Unauthenticated(ErrorStatus),
+    PermissionDenied(ErrorStatus),
+    NotFound(ErrorStatus),
+    Conflict(ErrorStatus),
+    RateLimited(ErrorStatus),
+    Unavailable(ErrorStatus),
+    Other(ErrorStatus),
+}
+
+impl ApiError {
+    fn from_status(status: ErrorStatus) -> Self {
+        match status.status.as_deref() {
+            Some("INVALID_ARGUMENT" | "FAILED_PRECONDITION" | "OUT_OF_RANGE") => {
+                Self::InvalidArgument(status)
+            }
+            Some("UNAUTHENTICATED") => Self::Unauthenticated(status),
+            Some("PERMISSION_DENIED") => Self::PermissionDenied(status),
+            Some("NOT_FOUND") => Self::NotFound(status),
+            Some("ALREADY_EXISTS" | "ABORTED") => Self::Conflict(status),
+            Some("RESOURCE_EXHAUSTED") => Self::RateLimited(status),
+            Some("UNAVAILABLE") => Self::Unavailable(status),
+            _ => match status.code {
+                400 => Self::InvalidArgument(status),
+                401 => Self::Unauthenticated(status),
+                403 => Self::PermissionDenied(status),
+                404 => Self::NotFound(status),
+                409 => Self::Conflict(status),
+                429 => Self::RateLimited(status),
+                503 => Self::Unavailable(status),
+                _ => Self::Other(status),
+            },
+        }
+    }
+
+    fn from_body(code: StatusCode, body: &str) -> Self {
+        let status = match serde_json::from_str::<ErrorEnvelope>(body) {
+            Ok(ErrorEnvelope {
+                error: ErrorBody::Status(status),
+                ..
+            }) => status,
+            Ok(ErrorEnvelope {
+                error: ErrorBody::Code(error),
+                error_description,
+            }) => ErrorStatus {
+                code: code.as_u16(),
+                message: error_description.unwrap_or_else(|| error.clone()),
+                status: Some(error),
+                details: Vec::new(),
+            },
+            Err(_) => ErrorStatus {
+                code: code.as_u16(),
+                message: if body.trim().is_empty() {
+                    code.canonical_reason()
+                        .unwrap_or("unknown error")
+                        .to_owned()
+                } else {
+                    body.trim().to_owned()
+                },
+                status: None,
+                details: Vec::new(),
+            },
+        };
+        Self::from_status(status)
+    }
+
+    pub async fn from_response(res: Response) -> Self {
+        let code = res.status();
+        let body = res.text().await.unwrap_or_default();
+        Self::from_body(code, &body)
+    }
+
+    pub fn status(&self) -> &ErrorStatus {
+        match self {
+            Self::InvalidArgument(status)
+            | Self::Unauthenticated(status)
+            | Self::PermissionDenied(status)
+            | Self::NotFound(status)
+            | Self::Conflict(status)
+            | Self::RateLimited(status)
+            | Self::Unavailable(status)
+            | Self::Other(status) => status,
+        }
+    }
+
+    /// Process exit code for scripts to tell failure kinds apart.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Other(_) => 10,
+            Self::InvalidArgument(_) => 11,
+            Self::Unauthenticated(_) => 12,
+            Self::PermissionDenied(_) => 13,
+            Self::NotFound(_) => 14,
+            Self::Conflict(_) => 15,
+            Self::RateLimited(_) => 16,
+            Self::Unavailable(_) => 17,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = self.status();
+        write!(f, "{} (HTTP {}", status.message, status.code)?;
+        if let Some(name) = &status.status {
+            write!(f, ", {name}")?;
+        }
+        write!(f, ")")?;
+        for detail in &status.details {
+            if let Some(reason) = detail.get("reason").and_then(|reason| reason.as_str()) {
+                write!(f, "\n  reason: {reason}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Turns a non-success response into an [`ApiError`] decoded from its body.
+pub async fn error_for_status(res: Response) -> Result<Response, ApiError> {
+    if res.status().is_success() {
+        Ok(res)
+    } else {
+        Err(ApiError::from_response(res).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_google_error_envelope() {
+        let body = r#"{"error": {"code": 403, "message": "denied", "status": "PERMISSION_DENIED",
+            "details": [{"reason": "forbidden"}]}}"#;
+        let err = ApiError::from_body(StatusCode::FORBIDDEN, body);
+        assert!(matches!(err, ApiError::PermissionDenied(_)));
+        assert_eq!(err.exit_code(), 13);
+        assert_eq!(err.status().message, "denied");
+        assert_eq!(err.status().details.len(), 1);
+    }
+
+    #[test]
+    fn parses_oauth_error() {
+        let body = r#"{"error": "invalid_grant", "error_description": "Invalid JWT Signature."}"#;
+        let err = ApiError::from_body(StatusCode::BAD_REQUEST, body);
+        assert!(matches!(err, ApiError::InvalidArgument(_)));
+        assert_eq!(err.status().status.as_deref(), Some("invalid_grant"));
+        assert_eq!(err.status().message, "Invalid JWT Signature.");
+    }
+
+    #[test]
+    fn keeps_non_json_body_as_message() {
+        let err = ApiError::from_body(StatusCode::BAD_GATEWAY, "<html>Bad Gateway</html>\n");
+        assert!(matches!(err, ApiError::Other(_)));
+        assert_eq!(err.status().code, 502);
+        assert_eq!(err.status().message, "<html>Bad Gateway</html>");
+    }
+}
@@ -0,0 +1,145 @@
+use crate::api::{ApiClient, ExpansionFile, ExpansionFileType};
+use crate::edit_guard::EditGuard;
+use crate::output::{self, cell, Committed, OutputFormat, Tabular};
+use clap::Subcommand;
+use std::path::PathBuf;
+
+#[derive(Subcommand, Debug)]
+pub enum ExpansionFilesCommand {
+    /// Show the expansion file of an APK
+    Get {
+        version_code: i64,
+        #[arg(value_enum)]
+        file_type: ExpansionFileType,
+    },
+    /// Upload an expansion file for an APK
+    Upload {
+        version_code: i64,
+        #[arg(value_enum)]
+        file_type: ExpansionFileType,
+        file: PathBuf,
+    },
+    /// Use the expansion file of another version code for an APK
+    Update {
+        version_code: i64,
+        #[arg(value_enum)]
+        file_type: ExpansionFileType,
+        /// Version code of the APK whose expansion file to use
+        #[arg(long)]
+        references_version: i64,
+    },
+    /// Like update, but only sends the fields that are given
+    Patch {
+        version_code: i64,
+        #[arg(value_enum)]
+        file_type: ExpansionFileType,
+        /// Version code of the APK whose expansion file to use
+        #[arg(long)]
+        references_version: i64,
+    },
+}
+
+pub async fn run(
+    client: &ApiClient,
+    command: ExpansionFilesCommand,
+    format: OutputFormat,
+) -> eyre::Result<()> {
+    match command {
+        ExpansionFilesCommand::Get {
+            version_code,
+            file_type,
+        } => {
+            let edit = EditGuard::open(client).await?;
+            let expansion_file = edit
+                .run(client.get_expansion_file(edit.id(), version_code, file_type))
+                .await?;
+            edit.discard().await?;
+            output::print(format, &expansion_file)
+        }
+        ExpansionFilesCommand::Upload {
+            version_code,
+            file_type,
+            file,
+        } => {
+            let file = tokio::fs::File::open(&file).await?;
+            let edit = EditGuard::open(client).await?;
+            let expansion_file = edit
+                .run(client.upload_expansion_file(edit.id(), version_code, file_type, file))
+                .await?
+                .expansion_file;
+            let committed = edit.commit().await?.is_committed();
+            output::print(
+                format,
+                &Committed {
+                    committed,
+                    result: expansion_file,
+                },
+            )
+        }
+        ExpansionFilesCommand::Update {
+            version_code,
+            file_type,
+            references_version,
+        } => {
+            let edit = EditGuard::open(client).await?;
+            let expansion_file = edit
+                .run(client.update_expansion_file(
+                    edit.id(),
+                    version_code,
+                    file_type,
+                    &ExpansionFile {
+                        references_version: Some(references_version),
+                        ..Default::default()
+                    },
+                ))
+                .await?;
+            let committed = edit.commit().await?.is_committed();
+            output::print(
+                format,
+                &Committed {
+                    committed,
+                    result: expansion_file,
+                },
+            )
+        }
+        ExpansionFilesCommand::Patch {
+            version_code,
+            file_type,
+            references_version,
+        } => {
+            let edit = EditGuard::open(client).await?;
+            let expansion_file = edit
+                .run(client.patch_expansion_file(
+                    edit.id(),
+                    version_code,
+                    file_type,
+                    &ExpansionFile {
+                        references_version: Some(references_version),
+                        ..Default::default()
+                    },
+                ))
+                .await?;
+            let committed = edit.commit().await?.is_committed();
+            output::print(
+                format,
+                &Committed {
+                    committed,
+                    result: expansion_file,
+                },
+            )
+        }
+    }
+}
+
+impl Tabular for ExpansionFile {
+    fn headers(&self) -> &'static [&'static str] {
+        &["FILE SIZE", "REFERENCES VERSION"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            cell(self.file_size.as_ref()),
+            cell(self.references_version),
+        ]]
+    }
+}
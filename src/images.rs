@@ -0,0 +1,308 @@
+use crate::api::{ApiClient, Image, ImageType};
+use crate::edit_guard::EditGuard;
+use crate::output::{self, cell, Changes, Committed, OutputFormat, Tabular};
+use clap::Subcommand;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+#[derive(Subcommand, Debug)]
+pub enum ImagesCommand {
+    /// List the images of a language and image type
+    List {
+        language: String,
+        #[arg(value_enum)]
+        image_type: ImageType,
+    },
+    /// Upload images, skipping those already on the store listing
+    Upload {
+        language: String,
+        #[arg(value_enum)]
+        image_type: ImageType,
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Delete a single image
+    Delete {
+        language: String,
+        #[arg(value_enum)]
+        image_type: ImageType,
+        image_id: String,
+    },
+    /// Delete all images of a language and image type
+    DeleteAll {
+        language: String,
+        #[arg(value_enum)]
+        image_type: ImageType,
+    },
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImageAction {
+    Uploaded,
+    Unchanged,
+    Deleted,
+}
+
+impl std::fmt::Display for ImageAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Uploaded => "uploaded",
+            Self::Unchanged => "unchanged",
+            Self::Deleted => "deleted",
+        })
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageChange {
+    pub language: String,
+    pub image_type: ImageType,
+    pub action: ImageAction,
+    pub file: Option<PathBuf>,
+    pub id: Option<String>,
+    pub sha256: String,
+}
+
+pub async fn run(
+    client: &ApiClient,
+    command: ImagesCommand,
+    format: OutputFormat,
+) -> eyre::Result<()> {
+    match command {
+        ImagesCommand::List {
+            language,
+            image_type,
+        } => {
+            let edit = EditGuard::open(client).await?;
+            let images = edit
+                .run(client.list_images(edit.id(), &language, image_type))
+                .await?
+                .images;
+            edit.discard().await?;
+            output::print(format, &images)
+        }
+        ImagesCommand::Upload {
+            language,
+            image_type,
+            files,
+        } => {
+            let images = read_images(&files)?;
+            let edit = EditGuard::open(client).await?;
+            let changes = edit
+                .run(async {
+                    let existing = client
+                        .list_images(edit.id(), &language, image_type)
+                        .await?
+                        .images;
+                    upload_images(client, edit.id(), &language, image_type, images, &existing).await
+                })
+                .await?;
+            let committed = if changes
+                .iter()
+                .any(|change| change.action == ImageAction::Uploaded)
+            {
+                edit.commit().await?.is_committed()
+            } else {
+                edit.discard().await?;
+                false
+            };
+            output::print(
+                format,
+                &Committed {
+                    committed,
+                    result: Changes { changes },
+                },
+            )
+        }
+        ImagesCommand::Delete {
+            language,
+            image_type,
+            image_id,
+        } => {
+            let edit = EditGuard::open(client).await?;
+            let deleted = edit
+                .run(async {
+                    let image = client
+                        .list_images(edit.id(), &language, image_type)
+                        .await?
+                        .images
+                        .into_iter()
+                        .find(|image| image.id == image_id)
+                        .ok_or_else(|| {
+                            eyre::eyre!("{language} has no {image_type} image {image_id}")
+                        })?;
+                    client
+                        .delete_image(edit.id(), &language, image_type, &image_id)
+                        .await?;
+                    Ok(image)
+                })
+                .await?;
+            let committed = edit.commit().await?.is_committed();
+            let changes = vec![ImageChange {
+                language,
+                image_type,
+                action: ImageAction::Deleted,
+                file: None,
+                id: Some(deleted.id),
+                sha256: deleted.sha256,
+            }];
+            output::print(
+                format,
+                &Committed {
+                    committed,
+                    result: Changes { changes },
+                },
+            )
+        }
+        ImagesCommand::DeleteAll {
+            language,
+            image_type,
+        } => {
+            let edit = EditGuard::open(client).await?;
+            let deleted = edit
+                .run(client.delete_all_images(edit.id(), &language, image_type))
+                .await?
+                .deleted;
+            let committed = edit.commit().await?.is_committed();
+            let changes: Vec<ImageChange> = deleted
+                .into_iter()
+                .map(|image| ImageChange {
+                    language: language.clone(),
+                    image_type,
+                    action: ImageAction::Deleted,
+                    file: None,
+                    id: Some(image.id),
+                    sha256: image.sha256,
+                })
+                .collect();
+            output::print(
+                format,
+                &Committed {
+                    committed,
+                    result: Changes { changes },
+                },
+            )
+        }
+    }
+}
+
+/// An image read from disk, ready to be uploaded.
+pub struct LocalImage {
+    pub path: PathBuf,
+    pub content_type: &'static str,
+    pub sha256: String,
+    pub bytes: Vec<u8>,
+}
+
+pub fn read_images(files: &[PathBuf]) -> eyre::Result<Vec<LocalImage>> {
+    files
+        .iter()
+        .map(|path| {
+            let bytes = std::fs::read(path)
+                .map_err(|err| eyre::eyre!("failed to read {}: {}", path.display(), err))?;
+            Ok(LocalImage {
+                path: path.clone(),
+                content_type: content_type(path)?,
+                sha256: sha256_hex(&bytes),
+                bytes,
+            })
+        })
+        .collect()
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn content_type(path: &Path) -> eyre::Result<&'static str> {
+    match path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("png") => Ok("image/png"),
+        Some("jpg" | "jpeg") => Ok("image/jpeg"),
+        _ => eyre::bail!("{} is not a PNG or JPEG image", path.display()),
+    }
+}
+
+/// Uploads the images whose sha256 doesn't match one of the `existing` images.
+pub async fn upload_images(
+    client: &ApiClient,
+    edit_id: &str,
+    language: &str,
+    image_type: ImageType,
+    images: Vec<LocalImage>,
+    existing: &[Image],
+) -> eyre::Result<Vec<ImageChange>> {
+    let mut changes = Vec::with_capacity(images.len());
+    for image in images {
+        let change = if let Some(current) = existing.iter().find(|e| e.sha256 == image.sha256) {
+            ImageChange {
+                language: language.to_owned(),
+                image_type,
+                action: ImageAction::Unchanged,
+                file: Some(image.path),
+                id: Some(current.id.clone()),
+                sha256: image.sha256,
+            }
+        } else {
+            let uploaded = client
+                .upload_image(
+                    edit_id,
+                    language,
+                    image_type,
+                    image.content_type,
+                    image.bytes,
+                )
+                .await?
+                .image;
+            ImageChange {
+                language: language.to_owned(),
+                image_type,
+                action: ImageAction::Uploaded,
+                file: Some(image.path),
+                id: Some(uploaded.id),
+                sha256: image.sha256,
+            }
+        };
+        changes.push(change);
+    }
+    Ok(changes)
+}
+
+impl Tabular for Vec<Image> {
+    fn headers(&self) -> &'static [&'static str] {
+        &["ID", "SHA256", "URL"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.iter()
+            .map(|image| vec![image.id.clone(), image.sha256.clone(), image.url.clone()])
+            .collect()
+    }
+}
+
+impl Tabular for Vec<ImageChange> {
+    fn headers(&self) -> &'static [&'static str] {
+        &["LANGUAGE", "TYPE", "ACTION", "FILE", "ID"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.iter()
+            .map(|change| {
+                vec![
+                    change.language.clone(),
+                    change.image_type.to_string(),
+                    change.action.to_string(),
+                    cell(change.file.as_ref().map(|file| file.display())),
+                    cell(change.id.as_ref()),
+                ]
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,362 @@
+use crate::api::{ApiClient, Listing};
+use crate::edit_guard::EditGuard;
+use crate::output::{self, cell, Changes, Committed, OutputFormat, Tabular};
+use clap::Subcommand;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+pub const MAX_TITLE_LENGTH: usize = 30;
+pub const MAX_SHORT_DESCRIPTION_LENGTH: usize = 80;
+pub const MAX_FULL_DESCRIPTION_LENGTH: usize = 4000;
+
+pub const TITLE_FILE: &str = "title.txt";
+pub const SHORT_DESCRIPTION_FILE: &str = "short_description.txt";
+pub const FULL_DESCRIPTION_FILE: &str = "full_description.txt";
+pub const VIDEO_FILE: &str = "video.txt";
+
+#[derive(Subcommand, Debug)]
+pub enum ListingsCommand {
+    /// List the store listings of all languages
+    List,
+    /// Show the store listing of a language
+    Get { language: String },
+    /// Change fields of the store listing of a language
+    Update {
+        language: String,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long)]
+        short_description: Option<String>,
+        #[arg(long)]
+        full_description: Option<String>,
+        /// YouTube URL of the promo video
+        #[arg(long)]
+        video: Option<String>,
+    },
+    /// Delete the store listing of a language
+    Delete { language: String },
+    /// Update store listings from a listings/<lang>/{title,short_description,..}.txt directory
+    Sync { dir: PathBuf },
+}
+
+pub async fn run(
+    client: &ApiClient,
+    command: ListingsCommand,
+    format: OutputFormat,
+) -> eyre::Result<()> {
+    match command {
+        ListingsCommand::List => {
+            let edit = EditGuard::open(client).await?;
+            let listings = edit.run(client.list_listings(edit.id())).await?.listings;
+            edit.discard().await?;
+            output::print(format, &listings)
+        }
+        ListingsCommand::Get { language } => {
+            let edit = EditGuard::open(client).await?;
+            let listing = edit.run(client.get_listing(edit.id(), &language)).await?;
+            edit.discard().await?;
+            output::print(format, &listing)
+        }
+        ListingsCommand::Update {
+            language,
+            title,
+            short_description,
+            full_description,
+            video,
+        } => {
+            let desired = Listing {
+                language,
+                title,
+                short_description,
+                full_description,
+                video,
+            };
+            validate(&desired)?;
+            let edit = EditGuard::open(client).await?;
+            let listing = edit
+                .run(async {
+                    let current = find_listing(client, edit.id(), &desired.language).await?;
+                    let listing = merge(current.as_ref(), &desired);
+                    client.update_listing(edit.id(), &listing).await
+                })
+                .await?;
+            let committed = edit.commit().await?.is_committed();
+            output::print(
+                format,
+                &Committed {
+                    committed,
+                    result: listing,
+                },
+            )
+        }
+        ListingsCommand::Delete { language } => {
+            let edit = EditGuard::open(client).await?;
+            edit.run(client.delete_listing(edit.id(), &language))
+                .await?;
+            let committed = edit.commit().await?.is_committed();
+            output::print(
+                format,
+                &Committed {
+                    committed,
+                    result: DeletedListing {
+                        language,
+                        deleted: true,
+                    },
+                },
+            )
+        }
+        ListingsCommand::Sync { dir } => {
+            let desired = read_dir(&dir)?;
+            for listing in &desired {
+                validate(listing)?;
+            }
+            let edit = EditGuard::open(client).await?;
+            let changes = edit
+                .run(async {
+                    let current = client.list_listings(edit.id()).await?.listings;
+                    let mut changes = Vec::new();
+                    for listing in &desired {
+                        let current = current
+                            .iter()
+                            .find(|current| current.language == listing.language);
+                        let listing_changes = diff(current, listing);
+                        if !listing_changes.is_empty() {
+                            client
+                                .update_listing(edit.id(), &merge(current, listing))
+                                .await?;
+                        }
+                        changes.extend(listing_changes);
+                    }
+                    Ok(changes)
+                })
+                .await?;
+            let committed = if changes.is_empty() {
+                eprintln!("store listings are up to date");
+                edit.discard().await?;
+                false
+            } else {
+                edit.commit().await?.is_committed()
+            };
+            output::print(
+                format,
+                &Committed {
+                    committed,
+                    result: Changes { changes },
+                },
+            )
+        }
+    }
+}
+
+/// Fetches the listing of a language, or `None` if the app has none for it yet.
+async fn find_listing(
+    client: &ApiClient,
+    edit_id: &str,
+    language: &str,
+) -> eyre::Result<Option<Listing>> {
+    let listings = client.list_listings(edit_id).await?.listings;
+    Ok(listings
+        .into_iter()
+        .find(|listing| listing.language == language))
+}
+
+pub fn validate(listing: &Listing) -> eyre::Result<()> {
+    let limits = [
+        ("title", &listing.title, MAX_TITLE_LENGTH),
+        (
+            "short description",
+            &listing.short_description,
+            MAX_SHORT_DESCRIPTION_LENGTH,
+        ),
+        (
+            "full description",
+            &listing.full_description,
+            MAX_FULL_DESCRIPTION_LENGTH,
+        ),
+    ];
+    for (field, value, limit) in limits {
+        let length = value.as_deref().map_or(0, |value| value.chars().count());
+        if length > limit {
+            eyre::bail!(
+                "{} of {} is {} characters long, the limit is {}",
+                field,
+                listing.language,
+                length,
+                limit
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Reads listings from `<dir>/<lang>/` directories. Files that are missing leave the
+/// corresponding field unchanged.
+pub fn read_dir(dir: &Path) -> eyre::Result<Vec<Listing>> {
+    let mut listings = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let path = entry.path();
+        let listing = Listing {
+            language: entry.file_name().to_string_lossy().into_owned(),
+            title: read_field(&path.join(TITLE_FILE))?,
+            short_description: read_field(&path.join(SHORT_DESCRIPTION_FILE))?,
+            full_description: read_field(&path.join(FULL_DESCRIPTION_FILE))?,
+            video: read_field(&path.join(VIDEO_FILE))?,
+        };
+        if listing.title.is_some()
+            || listing.short_description.is_some()
+            || listing.full_description.is_some()
+            || listing.video.is_some()
+        {
+            listings.push(listing);
+        }
+    }
+    listings.sort_by(|a, b| a.language.cmp(&b.language));
+    Ok(listings)
+}
+
+fn read_field(path: &Path) -> eyre::Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text.trim_end().to_owned())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(eyre::eyre!("failed to read {}: {}", path.display(), err)),
+    }
+}
+
+/// Overlays the fields set in `desired` onto `current`.
+pub fn merge(current: Option<&Listing>, desired: &Listing) -> Listing {
+    let mut listing = current.cloned().unwrap_or_else(|| Listing {
+        language: desired.language.clone(),
+        ..Default::default()
+    });
+    if desired.title.is_some() {
+        listing.title = desired.title.clone();
+    }
+    if desired.short_description.is_some() {
+        listing.short_description = desired.short_description.clone();
+    }
+    if desired.full_description.is_some() {
+        listing.full_description = desired.full_description.clone();
+    }
+    if desired.video.is_some() {
+        listing.video = desired.video.clone();
+    }
+    listing
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedListing {
+    language: String,
+    deleted: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub language: String,
+    pub field: &'static str,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// Lists the fields set in `desired` that differ from `current`.
+pub fn diff(current: Option<&Listing>, desired: &Listing) -> Vec<FieldChange> {
+    let fields = [
+        (
+            "title",
+            current.and_then(|c| c.title.as_ref()),
+            &desired.title,
+        ),
+        (
+            "shortDescription",
+            current.and_then(|c| c.short_description.as_ref()),
+            &desired.short_description,
+        ),
+        (
+            "fullDescription",
+            current.and_then(|c| c.full_description.as_ref()),
+            &desired.full_description,
+        ),
+        (
+            "video",
+            current.and_then(|c| c.video.as_ref()),
+            &desired.video,
+        ),
+    ];
+    fields
+        .into_iter()
+        .filter_map(|(field, old, new)| {
+            let new = new.as_ref()?;
+            (old != Some(new)).then(|| FieldChange {
+                language: desired.language.clone(),
+                field,
+                old: old.cloned(),
+                new: Some(new.clone()),
+            })
+        })
+        .collect()
+}
+
+impl Tabular for Listing {
+    fn headers(&self) -> &'static [&'static str] {
+        LISTING_HEADERS
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.language.clone(),
+            cell(self.title.as_ref()),
+            cell(self.short_description.as_ref()),
+            cell(self.video.as_ref()),
+        ]]
+    }
+}
+
+const LISTING_HEADERS: &[&str] = &["LANGUAGE", "TITLE", "SHORT DESCRIPTION", "VIDEO"];
+
+impl Tabular for Vec<Listing> {
+    fn headers(&self) -> &'static [&'static str] {
+        LISTING_HEADERS
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.iter().flat_map(Listing::rows).collect()
+    }
+}
+
+impl Tabular for DeletedListing {
+    fn headers(&self) -> &'static [&'static str] {
+        &["LANGUAGE", "DELETED"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![self.language.clone(), self.deleted.to_string()]]
+    }
+}
+
+impl Tabular for Vec<FieldChange> {
+    fn headers(&self) -> &'static [&'static str] {
+        &["LANGUAGE", "FIELD", "CHANGE"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.iter()
+            .map(|change| {
+                let kind = if change.old.is_none() {
+                    "added"
+                } else {
+                    "changed"
+                };
+                vec![
+                    change.language.clone(),
+                    change.field.to_owned(),
+                    kind.to_owned(),
+                ]
+            })
+            .collect()
+    }
+}
@@ -1,11 +1,34 @@
+mod api;
+mod details;
+mod edit_guard;
+mod edits;
+mod error;
+mod expansion_files;
+mod images;
+mod listings;
 mod oauth2;
-
-use clap::Parser;
-use futures::prelude::*;
+mod output;
+mod plan;
+mod promote;
+mod pull;
+mod release_notes;
+mod retry;
+mod rollout;
+mod sync;
+mod testers;
+mod tracks;
+mod upload;
+
+use api::{ApiClient, ANDROID_PUBLISHER_SCOPE, DEFAULT_SERVICE_ENDPOINT};
+use clap::{Parser, Subcommand};
+use edit_guard::CommitMode;
+use error::ApiError;
 use oauth2::Oauth2TokenManager;
-use reqwest::{Body, Client, Url};
-use serde::{Deserialize, Serialize};
+use output::OutputFormat;
+use reqwest::Url;
+use retry::RetryPolicy;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about=None)]
@@ -14,205 +37,108 @@ struct Args {
     service_account_json: PathBuf,
     #[arg(short, long)]
     package_name: String,
-    #[arg(short, long)]
-    bundle: PathBuf,
-    #[arg(short, long)]
-    version_code: String,
-}
-
-const ANDROID_PUBLISHER_SCOPE: &str = "https://www.googleapis.com/auth/androidpublisher";
-
-pub struct ApiClient {
-    client: Client,
-    package_name: String,
-    token_manager: Oauth2TokenManager,
-    service_endpoint: Url,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct AppEdit {
-    id: String,
-    expiry_time_seconds: String,
+    #[arg(long, default_value = DEFAULT_SERVICE_ENDPOINT)]
+    endpoint: Url,
+    /// How many times to retry requests failing with transient errors
+    #[arg(long, default_value_t = 4)]
+    max_retries: u32,
+    /// Initial delay before retrying a failed request, doubled with each attempt
+    #[arg(long, default_value_t = 500)]
+    retry_delay_ms: u64,
+    /// Format of the results printed on stdout
+    #[arg(short, long, value_enum, default_value = "table", global = true)]
+    output: OutputFormat,
+    /// Print the changes an edit would make instead of committing it
+    #[arg(long, global = true, conflicts_with = "confirm")]
+    plan: bool,
+    /// Print the changes an edit would make and ask before committing it
+    #[arg(long, global = true)]
+    confirm: bool,
+    #[command(subcommand)]
+    command: Command,
 }
 
-// #[derive(Serialize, Deserialize, Debug)]
-// #[serde(rename_all = "camelCase")]
-// pub struct TrackList {
-//     kind: String,
-//     tracks: Vec<Track>,
-// }
-
-#[derive(Serialize, Deserialize, Debug, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct Track {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    track: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    releases: Option<Vec<Release>>,
-}
-
-#[derive(Serialize, Deserialize, Debug, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct Release {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    version_codes: Option<Vec<String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    status: Option<String>,
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Upload a bundle and release it to a track
+    Upload(upload::UploadArgs),
+    /// Copy the newest release of one track to another without re-uploading
+    Promote(promote::PromoteArgs),
+    /// Manage the staged rollout of a release
+    Rollout(rollout::RolloutArgs),
+    /// List and inspect tracks
+    #[command(subcommand)]
+    Tracks(tracks::TracksCommand),
+    /// Manage store listings
+    #[command(subcommand)]
+    Listings(listings::ListingsCommand),
+    /// Manage store listing images
+    #[command(subcommand)]
+    Images(images::ImagesCommand),
+    /// Manage who can access closed testing tracks
+    #[command(subcommand)]
+    Testers(testers::TestersCommand),
+    /// Manage the default language and contact details
+    #[command(subcommand)]
+    Details(details::DetailsCommand),
+    /// Manage the expansion (OBB) files of APKs
+    #[command(subcommand)]
+    ExpansionFiles(expansion_files::ExpansionFilesCommand),
+    /// Reconcile listings, images, changelogs and details with a fastlane-style directory
+    Sync(sync::SyncArgs),
+    /// Download listings, images, changelogs and details into a fastlane-style directory
+    Pull(pull::PullArgs),
+    /// Manage edits directly
+    #[command(subcommand)]
+    Edits(edits::EditsCommand),
 }
 
-impl ApiClient {
-    pub fn new(package_name: String, token_manager: Oauth2TokenManager) -> Self {
-        Self {
-            client: Client::new(),
-            package_name,
-            token_manager,
-            service_endpoint: "https://androidpublisher.googleapis.com".parse().unwrap(),
-        }
-    }
-
-    fn url(&self, path: impl AsRef<str>) -> Url {
-        let mut url = self.service_endpoint.clone();
-        url.set_path(path.as_ref());
-        url
-    }
-
-    async fn create_edit(&self) -> eyre::Result<AppEdit> {
-        let res = self
-            .client
-            .post(self.url(format!(
-                "/androidpublisher/v3/applications/{}/edits",
-                self.package_name
-            )))
-            .bearer_auth(self.token_manager.token().await?.access_token)
-            .json(&serde_json::Value::Object(Default::default()))
-            .send()
-            .await?;
-        if let Err(err) = res.error_for_status_ref() {
-            println!("{}", res.text().await?);
-            Err(err.into())
-        } else {
-            Ok(res.json().await?)
-        }
-    }
-
-    async fn commit_edit(&self, edit_id: &str) -> eyre::Result<AppEdit> {
-        let res = self
-            .client
-            .post(self.url(format!(
-                "/androidpublisher/v3/applications/{}/edits/{}:commit",
-                self.package_name, edit_id
-            )))
-            .bearer_auth(self.token_manager.token().await?.access_token)
-            .header("Content-Length", "0")
-            .send()
-            .await?;
-        if let Err(err) = res.error_for_status_ref() {
-            println!("{}", res.text().await?);
-            Err(err.into())
-        } else {
-            Ok(res.json().await?)
-        }
-    }
-
-    async fn upload_bundle(&self, edit_id: &str, bundle: tokio::fs::File) -> eyre::Result<()> {
-        let total_size = bundle.metadata().await.unwrap().len();
-        let mut reader_stream = tokio_util::io::ReaderStream::new(bundle);
-        let mut uploaded = 0;
-        let bar = indicatif::ProgressBar::new(total_size);
-
-        let async_stream = async_stream::stream! {
-            while let Some(chunk) = reader_stream.next().await {
-                if let Ok(chunk) = &chunk {
-                    let new = total_size.min(uploaded + (chunk.len() as u64));
-                    uploaded = new;
-                    bar.set_position(new);
-                    if(uploaded >= total_size){
-                        bar.finish();
-                    }
-                }
-                yield chunk;
-            }
-        };
-
-        let res = self
-            .client
-            .post(self.url(format!(
-                "/upload/androidpublisher/v3/applications/{}/edits/{}/bundles",
-                self.package_name, edit_id,
-            )))
-            .bearer_auth(self.token_manager.token().await?.access_token)
-            .header("Content-type", "application/octet-stream")
-            .body(Body::wrap_stream(async_stream))
-            .send()
-            .await?;
-        if let Err(err) = res.error_for_status_ref() {
-            println!("{}", res.text().await?);
-            Err(err.into())
-        } else {
-            Ok(())
-        }
-    }
-
-    // async fn list_tracks(&self, edit_id: &str) -> eyre::Result<TrackList> {
-    //     let res = self
-    //         .client
-    //         .get(self.url(format!(
-    //             "/androidpublisher/v3/applications/{}/edits/{}/tracks",
-    //             self.package_name, edit_id
-    //         )))
-    //         .bearer_auth(self.token_manager.token().await?.access_token)
-    //         .send()
-    //         .await?;
-    //     if let Err(err) = res.error_for_status_ref() {
-    //         println!("{}", res.text().await?);
-    //         Err(err.into())
-    //     } else {
-    //         Ok(res.json().await?)
-    //     }
-    // }
-
-    async fn update_track(&self, edit_id: &str, version_code: String) -> eyre::Result<()> {
-        let res = self
-            .client
-            .put(self.url(format!(
-                "/androidpublisher/v3/applications/{}/edits/{}/tracks/internal",
-                self.package_name, edit_id
-            )))
-            .bearer_auth(self.token_manager.token().await?.access_token)
-            .json(&Track {
-                releases: Some(vec![Release {
-                    status: Some("draft".into()),
-                    version_codes: Some(vec![version_code]),
-                    ..Default::default()
-                }]),
-                ..Default::default()
-            })
-            .send()
-            .await?;
-        if let Err(err) = res.error_for_status_ref() {
-            println!("{}", res.text().await?);
-            Err(err.into())
-        } else {
-            Ok(())
-        }
+#[tokio::main]
+async fn main() {
+    if let Err(err) = run(Args::parse()).await {
+        eprintln!("Error: {err:?}");
+        let exit_code = err
+            .downcast_ref::<ApiError>()
+            .map(ApiError::exit_code)
+            .unwrap_or(1);
+        std::process::exit(exit_code);
     }
 }
 
-#[tokio::main]
-async fn main() -> eyre::Result<()> {
-    let args = Args::parse();
+async fn run(args: Args) -> eyre::Result<()> {
     let service_account = serde_json::from_reader(std::fs::File::open(args.service_account_json)?)?;
-    let bundle = tokio::fs::File::open(args.bundle).await?;
-    let token_manager = Oauth2TokenManager::new(service_account, [ANDROID_PUBLISHER_SCOPE]);
-    let client = ApiClient::new(args.package_name, token_manager);
-
-    let edit = client.create_edit().await?;
-    client.upload_bundle(&edit.id, bundle).await?;
-    client.update_track(&edit.id, args.version_code).await?;
-    client.commit_edit(&edit.id).await?;
-
-    Ok(())
+    let retry_policy = RetryPolicy {
+        max_retries: args.max_retries,
+        initial_delay: Duration::from_millis(args.retry_delay_ms),
+        ..Default::default()
+    };
+    let token_manager = Oauth2TokenManager::new(service_account, [ANDROID_PUBLISHER_SCOPE])
+        .with_retry_policy(retry_policy.clone());
+    let commit_mode = if args.plan {
+        CommitMode::Plan
+    } else if args.confirm {
+        CommitMode::Confirm
+    } else {
+        CommitMode::Commit
+    };
+    let client = ApiClient::new(args.package_name, token_manager, args.endpoint)
+        .with_retry_policy(retry_policy)
+        .with_commit_mode(commit_mode);
+
+    match args.command {
+        Command::Upload(upload_args) => upload::run(&client, upload_args, args.output).await,
+        Command::Promote(promote_args) => promote::run(&client, promote_args, args.output).await,
+        Command::Rollout(rollout_args) => rollout::run(&client, rollout_args, args.output).await,
+        Command::Tracks(command) => tracks::run(&client, command, args.output).await,
+        Command::Listings(command) => listings::run(&client, command, args.output).await,
+        Command::Images(command) => images::run(&client, command, args.output).await,
+        Command::Testers(command) => testers::run(&client, command, args.output).await,
+        Command::Details(command) => details::run(&client, command, args.output).await,
+        Command::ExpansionFiles(command) => {
+            expansion_files::run(&client, command, args.output).await
+        }
+        Command::Sync(sync_args) => sync::run(&client, sync_args, args.output).await,
+        Command::Pull(pull_args) => pull::run(&client, pull_args, args.output).await,
+        Command::Edits(command) => edits::run(&client, command, args.output).await,
+    }
 }
@@ -1,3 +1,5 @@
+use crate::error::error_for_status;
+use crate::retry::{RetryPolicy, SendWithRetry};
 use eyre::Result;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -89,6 +91,7 @@ pub struct Oauth2TokenManager {
     service_account: ServiceAccount,
     scope: String,
     token: Arc<Mutex<Option<Oauth2Token>>>,
+    retry_policy: RetryPolicy,
 }
 
 impl Oauth2TokenManager {
@@ -97,9 +100,15 @@ impl Oauth2TokenManager {
             service_account,
             scope: scopes.as_ref().join(","),
             token: Arc::new(Mutex::new(None)),
+            retry_policy: RetryPolicy::default(),
         }
     }
 
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
     pub async fn token(&self) -> Result<Oauth2Token> {
         let mut token = self.token.lock().await;
         if let Some(token) = token.as_ref() {
@@ -112,17 +121,16 @@ impl Oauth2TokenManager {
         Ok(new_token)
     }
 
-    async fn request_access_token<'a>(&self) -> Result<Oauth2Token> {
-        let token_res: TokenResponse = OAUTH2_CLIENT
+    async fn request_access_token(&self) -> Result<Oauth2Token> {
+        let res = OAUTH2_CLIENT
             .post(TOKEN_ENDPOINT)
             .form(&TokenRequest::build(
                 &self.service_account,
                 &Jwt::new(&self.service_account, self.scope.clone()),
             ))
-            .send()
-            .await?
-            .json()
+            .send_with_retry(&self.retry_policy)
             .await?;
+        let token_res: TokenResponse = error_for_status(res).await?.json().await?;
         Ok(Oauth2Token {
             access_token: token_res.access_token.trim_end_matches('.').to_string(),
             expires_at: time::OffsetDateTime::now_utc()
@@ -0,0 +1,108 @@
+use clap::ValueEnum;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Aligned columns with a header row
+    Table,
+    /// Pretty-printed JSON
+    Json,
+    /// Tab-separated columns without a header, for shell scripts
+    Plain,
+}
+
+/// A command result that can be rendered as rows of a table.
+pub trait Tabular: Serialize {
+    fn headers(&self) -> &'static [&'static str];
+    fn rows(&self) -> Vec<Vec<String>>;
+}
+
+/// The result of a command that writes to an edit, together with whether the edit was
+/// committed. It isn't under `--plan`, or when there was nothing to change.
+#[derive(Debug, Serialize)]
+pub struct Committed<T> {
+    pub committed: bool,
+    #[serde(flatten)]
+    pub result: T,
+}
+
+impl<T: Tabular> Tabular for Committed<T> {
+    fn headers(&self) -> &'static [&'static str] {
+        self.result.headers()
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.result.rows()
+    }
+}
+
+/// A list of changes, so that it can be flattened into [`Committed`].
+#[derive(Debug, Serialize)]
+pub struct Changes<T> {
+    pub changes: Vec<T>,
+}
+
+impl<T: Serialize> Tabular for Changes<T>
+where
+    Vec<T>: Tabular,
+{
+    fn headers(&self) -> &'static [&'static str] {
+        self.changes.headers()
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.changes.rows()
+    }
+}
+
+/// Prints a command result on stdout. Progress and logs go to stderr so that stdout can be
+/// piped into other tools.
+pub fn print<T: Tabular>(format: OutputFormat, value: &T) -> eyre::Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        OutputFormat::Table => print_table(value.headers(), &value.rows()),
+        OutputFormat::Plain => {
+            for row in value.rows() {
+                println!("{}", row.join("\t"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Renders the change of a value as `  - old` / `  + new` lines, to print under a heading.
+pub fn diff_lines(old: Option<&str>, new: Option<&str>) -> Vec<String> {
+    let old = old
+        .into_iter()
+        .flat_map(str::lines)
+        .map(|line| format!("  - {line}"));
+    let new = new
+        .into_iter()
+        .flat_map(str::lines)
+        .map(|line| format!("  + {line}"));
+    old.chain(new).collect()
+}
+
+/// Formats an optional cell, using `-` for missing values.
+pub fn cell<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_owned(), |value| value.to_string())
+}
+
+/// Prints rows as columns padded to the widest cell.
+fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let headers: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+    for row in std::iter::once(&headers).chain(rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+}
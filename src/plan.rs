@@ -0,0 +1,191 @@
+use crate::api::{ApiClient, ImageType, Testers, Track};
+use crate::details;
+use crate::edit_guard::EditGuard;
+use crate::error::ApiError;
+use crate::listings;
+use crate::output;
+use std::io::Write;
+
+/// Prints what committing `edit_id` would change, comparing it with the live state read
+/// through a second edit. Uploaded deobfuscation and expansion files can't be read back, so
+/// they aren't part of the diff.
+pub async fn print_diff(client: &ApiClient, edit_id: &str) -> eyre::Result<()> {
+    let base = EditGuard::open(client).await?;
+    let lines = base.run(diff(client, base.id(), edit_id)).await?;
+    base.discard().await?;
+    if lines.is_empty() {
+        eprintln!("no changes to tracks, testers, details, listings or images");
+    }
+    for line in &lines {
+        eprintln!("{line}");
+    }
+    Ok(())
+}
+
+/// Asks on the terminal whether to go ahead with the commit.
+pub async fn confirm() -> eyre::Result<bool> {
+    eprint!("Commit these changes? [y/N] ");
+    std::io::stderr().flush()?;
+    // read on a blocking thread so that Ctrl-C can still interrupt the prompt
+    let answer = tokio::task::spawn_blocking(|| {
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).map(|_| answer)
+    })
+    .await??;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+async fn diff(client: &ApiClient, base_id: &str, edit_id: &str) -> eyre::Result<Vec<String>> {
+    let mut lines = Vec::new();
+
+    let old_tracks = client.list_tracks(base_id).await?.tracks;
+    for track in client.list_tracks(edit_id).await?.tracks {
+        let old = old_tracks
+            .iter()
+            .find(|old| old.track == track.track)
+            .map(track_lines)
+            .unwrap_or_default();
+        let name = track.track.as_deref().unwrap_or_default();
+        push_changed(
+            &mut lines,
+            format!("~ track {name}"),
+            &old,
+            &track_lines(&track),
+        );
+
+        let old = get_testers(client, base_id, name).await?;
+        let new = get_testers(client, edit_id, name).await?;
+        push_changed(
+            &mut lines,
+            format!("~ testers {name}"),
+            &old.google_groups,
+            &new.google_groups,
+        );
+    }
+
+    let old_details = client.get_details(base_id).await?;
+    let new_details = client.get_details(edit_id).await?;
+    for (field, old, new) in details::diff(&old_details, &new_details) {
+        lines.push(format!("~ details {field}"));
+        lines.extend(output::diff_lines(old.as_deref(), new.as_deref()));
+    }
+
+    let old_listings = client.list_listings(base_id).await?.listings;
+    let new_listings = client.list_listings(edit_id).await?.listings;
+    for listing in &old_listings {
+        if !new_listings
+            .iter()
+            .any(|new| new.language == listing.language)
+        {
+            lines.push(format!("- listing {}", listing.language));
+        }
+    }
+    for listing in &new_listings {
+        let old = old_listings
+            .iter()
+            .find(|old| old.language == listing.language);
+        for change in listings::diff(old, listing) {
+            lines.push(format!("~ listing {} {}", change.language, change.field));
+            lines.extend(output::diff_lines(
+                change.old.as_deref(),
+                change.new.as_deref(),
+            ));
+        }
+
+        for image_type in ImageType::ALL {
+            let new_images = client
+                .list_images(edit_id, &listing.language, image_type)
+                .await?
+                .images;
+            let old_images = match old {
+                Some(_) => {
+                    client
+                        .list_images(base_id, &listing.language, image_type)
+                        .await?
+                        .images
+                }
+                None => Vec::new(),
+            };
+            for image in &old_images {
+                if !new_images.iter().any(|new| new.sha256 == image.sha256) {
+                    lines.push(format!(
+                        "- image {} {} {}",
+                        listing.language, image_type, image.id
+                    ));
+                }
+            }
+            for image in &new_images {
+                if !old_images.iter().any(|old| old.sha256 == image.sha256) {
+                    lines.push(format!(
+                        "+ image {} {} {}",
+                        listing.language, image_type, image.id
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(lines)
+}
+
+/// Fetches the testers of a track, treating tracks that can't have testers as having none.
+async fn get_testers(client: &ApiClient, edit_id: &str, track: &str) -> eyre::Result<Testers> {
+    match client.get_testers(edit_id, track).await {
+        Err(err)
+            if matches!(
+                err.downcast_ref::<ApiError>(),
+                Some(ApiError::InvalidArgument(_) | ApiError::NotFound(_))
+            ) =>
+        {
+            Ok(Testers::default())
+        }
+        result => result,
+    }
+}
+
+/// Describes the releases of a track, one line per release and per release note.
+fn track_lines(track: &Track) -> Vec<String> {
+    let mut lines = Vec::new();
+    for release in track.releases.iter().flatten() {
+        let mut line = format!(
+            "release {} [{}] {}",
+            release.name.as_deref().unwrap_or("-"),
+            release
+                .version_codes
+                .iter()
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", "),
+            release
+                .status
+                .map_or_else(|| "-".to_owned(), |status| status.to_string()),
+        );
+        if let Some(user_fraction) = release.user_fraction {
+            line += &format!(" {user_fraction}");
+        }
+        lines.push(line);
+        for note in release.release_notes.iter().flatten() {
+            lines.push(format!(
+                "  notes {}: {}",
+                note.language,
+                note.text.replace('\n', " ")
+            ));
+        }
+    }
+    lines
+}
+
+/// Adds `title` followed by the lines only in `old` and only in `new`, if there are any.
+fn push_changed(lines: &mut Vec<String>, title: String, old: &[String], new: &[String]) {
+    if old == new {
+        return;
+    }
+    lines.push(title);
+    for line in old.iter().filter(|line| !new.contains(line)) {
+        lines.push(format!("  - {line}"));
+    }
+    for line in new.iter().filter(|line| !old.contains(line)) {
+        lines.push(format!("  + {line}"));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::api::{ApiClient, ReleaseStatus};
+use crate::edit_guard::EditGuard;
+use crate::output::{self, Committed, OutputFormat};
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct PromoteArgs {
+    /// Track to take the newest release from
+    #[arg(long)]
+    from: String,
+    /// Track to release it to
+    #[arg(long)]
+    to: String,
+    /// Status of the promoted release, defaults to the status on the source track
+    #[arg(long, value_enum)]
+    status: Option<ReleaseStatus>,
+    /// Fraction of users to roll out to, only valid for inProgress releases (e.g. 0.05)
+    #[arg(long)]
+    user_fraction: Option<f64>,
+}
+
+pub async fn run(client: &ApiClient, args: PromoteArgs, format: OutputFormat) -> eyre::Result<()> {
+    let edit = EditGuard::open(client).await?;
+    let track = edit
+        .run(async {
+            let source = client.get_track(edit.id(), &args.from).await?;
+            let mut release = source
+                .releases
+                .into_iter()
+                .flatten()
+                .max_by_key(|release| release.max_version_code())
+                .ok_or_else(|| eyre::eyre!("track {} has no releases to promote", args.from))?;
+            if let Some(status) = args.status {
+                release.status = Some(status);
+                if !matches!(status, ReleaseStatus::InProgress | ReleaseStatus::Halted) {
+                    release.user_fraction = None;
+                }
+            }
+            if args.user_fraction.is_some() {
+                release.user_fraction = args.user_fraction;
+            }
+            release.validate()?;
+            client
+                .update_track(edit.id(), &args.to, vec![release])
+                .await
+        })
+        .await?;
+    let committed = edit.commit().await?.is_committed();
+
+    output::print(
+        format,
+        &Committed {
+            committed,
+            result: track,
+        },
+    )
+}
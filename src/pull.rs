@@ -0,0 +1,155 @@
+use crate::api::{ApiClient, ImageType, Listing};
+use crate::edit_guard::EditGuard;
+use crate::images::sha256_hex;
+use crate::listings::{FULL_DESCRIPTION_FILE, SHORT_DESCRIPTION_FILE, TITLE_FILE, VIDEO_FILE};
+use crate::output::{self, OutputFormat, Tabular};
+use crate::sync::{self, CHANGELOGS_DIR, DETAILS_FILE, IMAGES_DIR, IMAGE_EXTENSIONS};
+use clap::Args;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Debug)]
+pub struct PullArgs {
+    /// Directory to write the metadata to, in the layout read by `sync`
+    dir: PathBuf,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PulledFile {
+    pub kind: &'static str,
+    pub path: PathBuf,
+}
+
+pub async fn run(client: &ApiClient, args: PullArgs, format: OutputFormat) -> eyre::Result<()> {
+    let edit = EditGuard::open(client).await?;
+    let files = edit.run(pull(client, edit.id(), &args.dir)).await?;
+    edit.discard().await?;
+    output::print(format, &files)
+}
+
+async fn pull(client: &ApiClient, edit_id: &str, dir: &Path) -> eyre::Result<Vec<PulledFile>> {
+    let mut files = Vec::new();
+    std::fs::create_dir_all(dir)?;
+
+    let details = client.get_details(edit_id).await?;
+    let path = dir.join(DETAILS_FILE);
+    std::fs::write(&path, serde_json::to_string_pretty(&details)? + "\n")?;
+    files.push(PulledFile {
+        kind: "details",
+        path,
+    });
+
+    for listing in client.list_listings(edit_id).await?.listings {
+        let language_dir = dir.join(&listing.language);
+        files.extend(write_listing(&language_dir, &listing)?);
+        for image_type in ImageType::ALL {
+            let images = client
+                .list_images(edit_id, &listing.language, image_type)
+                .await?
+                .images;
+            let images_dir = language_dir.join(IMAGES_DIR);
+            remove_images(&images_dir, image_type)?;
+            for (index, image) in images.iter().enumerate() {
+                let bytes = client.download_image(image).await?;
+                if sha256_hex(&bytes) != image.sha256 {
+                    eyre::bail!("downloaded image {} doesn't match its sha256", image.id);
+                }
+                let ext = if bytes.starts_with(b"\x89PNG") {
+                    "png"
+                } else {
+                    "jpg"
+                };
+                let path = if image_type.is_single() {
+                    std::fs::create_dir_all(&images_dir)?;
+                    images_dir.join(format!("{image_type}.{ext}"))
+                } else {
+                    let type_dir = images_dir.join(image_type.to_string());
+                    std::fs::create_dir_all(&type_dir)?;
+                    type_dir.join(format!("{:02}.{ext}", index + 1))
+                };
+                std::fs::write(&path, bytes)?;
+                files.push(PulledFile {
+                    kind: "image",
+                    path,
+                });
+            }
+        }
+    }
+
+    for track in client.list_tracks(edit_id).await?.tracks {
+        for release in track.releases.into_iter().flatten() {
+            let version_code = match release.max_version_code() {
+                Some(version_code) => version_code,
+                None => continue,
+            };
+            for note in release.release_notes.into_iter().flatten() {
+                let changelogs_dir = dir.join(&note.language).join(CHANGELOGS_DIR);
+                std::fs::create_dir_all(&changelogs_dir)?;
+                let path = changelogs_dir.join(format!("{version_code}.txt"));
+                std::fs::write(&path, note.text + "\n")?;
+                files.push(PulledFile {
+                    kind: "release notes",
+                    path,
+                });
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+fn write_listing(dir: &Path, listing: &Listing) -> eyre::Result<Vec<PulledFile>> {
+    std::fs::create_dir_all(dir)?;
+    let fields = [
+        (TITLE_FILE, &listing.title),
+        (SHORT_DESCRIPTION_FILE, &listing.short_description),
+        (FULL_DESCRIPTION_FILE, &listing.full_description),
+        (VIDEO_FILE, &listing.video),
+    ];
+    let mut files = Vec::new();
+    for (name, value) in fields {
+        let path = dir.join(name);
+        match value {
+            Some(value) => {
+                std::fs::write(&path, format!("{value}\n"))?;
+                files.push(PulledFile {
+                    kind: "listing",
+                    path,
+                });
+            }
+            None if path.exists() => std::fs::remove_file(&path)?,
+            None => {}
+        }
+    }
+    Ok(files)
+}
+
+/// Removes the local images of a type so that images deleted on the server don't linger.
+fn remove_images(images_dir: &Path, image_type: ImageType) -> eyre::Result<()> {
+    if image_type.is_single() {
+        for ext in IMAGE_EXTENSIONS {
+            let path = images_dir.join(format!("{image_type}.{ext}"));
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+    } else if let Some(files) = sync::image_files(images_dir, image_type)? {
+        for path in files {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+impl Tabular for Vec<PulledFile> {
+    fn headers(&self) -> &'static [&'static str] {
+        &["KIND", "FILE"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.iter()
+            .map(|file| vec![file.kind.to_owned(), file.path.display().to_string()])
+            .collect()
+    }
+}
@@ -0,0 +1,94 @@
+use crate::api::LocalizedText;
+use std::path::{Path, PathBuf};
+
+pub const MAX_RELEASE_NOTES_LENGTH: usize = 500;
+
+const WHATSNEW_PREFIX: &str = "whatsnew-";
+const DEFAULT_NOTES_FILE: &str = "default.txt";
+
+/// Parses a `<lang>=<file>` pair given on the command line.
+pub fn parse_release_notes_arg(arg: &str) -> Result<(String, PathBuf), String> {
+    match arg.split_once('=') {
+        Some((language, path)) if !language.is_empty() && !path.is_empty() => {
+            Ok((language.to_owned(), PathBuf::from(path)))
+        }
+        _ => Err(format!("expected <lang>=<file>, got `{arg}`")),
+    }
+}
+
+/// Reads release notes from explicit `<lang>=<file>` pairs and an optional directory.
+///
+/// The directory may either contain `whatsnew-<lang>` files, or `<lang>/` subdirectories
+/// holding `<track>.txt` or `default.txt`. Explicit pairs take precedence over the directory.
+pub fn load(
+    files: &[(String, PathBuf)],
+    dir: Option<&Path>,
+    track: &str,
+) -> eyre::Result<Vec<LocalizedText>> {
+    let mut notes = Vec::new();
+    if let Some(dir) = dir {
+        notes = load_dir(dir, track)?;
+    }
+    for (language, path) in files {
+        let text = read_notes(path)?;
+        notes.retain(|note| &note.language != language);
+        notes.push(LocalizedText {
+            language: language.clone(),
+            text,
+        });
+    }
+    notes.sort_by(|a, b| a.language.cmp(&b.language));
+    for note in &notes {
+        validate(note)?;
+    }
+    Ok(notes)
+}
+
+pub fn validate(note: &LocalizedText) -> eyre::Result<()> {
+    let length = note.text.chars().count();
+    if length > MAX_RELEASE_NOTES_LENGTH {
+        eyre::bail!(
+            "release notes for {} are {} characters long, the limit is {}",
+            note.language,
+            length,
+            MAX_RELEASE_NOTES_LENGTH
+        );
+    }
+    Ok(())
+}
+
+fn load_dir(dir: &Path, track: &str) -> eyre::Result<Vec<LocalizedText>> {
+    let mut notes = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if entry.file_type()?.is_dir() {
+            let track_file = path.join(format!("{track}.txt"));
+            let file = if track_file.is_file() {
+                track_file
+            } else {
+                path.join(DEFAULT_NOTES_FILE)
+            };
+            if file.is_file() {
+                notes.push(LocalizedText {
+                    language: name,
+                    text: read_notes(&file)?,
+                });
+            }
+        } else if let Some(language) = name.strip_prefix(WHATSNEW_PREFIX) {
+            let language = language.strip_suffix(".txt").unwrap_or(language);
+            notes.push(LocalizedText {
+                language: language.to_owned(),
+                text: read_notes(&path)?,
+            });
+        }
+    }
+    Ok(notes)
+}
+
+fn read_notes(path: &Path) -> eyre::Result<String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| eyre::eyre!("failed to read {}: {}", path.display(), err))?;
+    Ok(text.trim().to_owned())
+}
@@ -0,0 +1,104 @@
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 4,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Full-jitter exponential backoff: a random delay between zero and
+    /// `initial_delay * 2^attempt`, capped at `max_delay`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let ceiling = self
+            .initial_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let random = RandomState::new().build_hasher().finish();
+        ceiling.mul_f64((random as f64) / (u64::MAX as f64))
+    }
+
+    fn delay_for(&self, attempt: u32, res: Option<&Response>) -> Duration {
+        res.and_then(retry_after)
+            .map(|delay| delay.min(self.max_delay))
+            .unwrap_or_else(|| self.backoff(attempt))
+    }
+}
+
+pub fn is_transient_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+pub fn is_transient_error(err: &reqwest::Error) -> bool {
+    err.is_connect() || err.is_timeout()
+}
+
+fn retry_after(res: &Response) -> Option<Duration> {
+    let seconds = res
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+pub(crate) trait SendWithRetry {
+    /// Sends the request, retrying transient failures according to `policy`. Requests
+    /// whose body cannot be cloned (e.g. streams) are only sent once.
+    async fn send_with_retry(self, policy: &RetryPolicy) -> reqwest::Result<Response>;
+}
+
+impl SendWithRetry for RequestBuilder {
+    async fn send_with_retry(self, policy: &RetryPolicy) -> reqwest::Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let request = match self.try_clone() {
+                Some(request) if attempt < policy.max_retries => request,
+                _ => return self.send().await,
+            };
+            let delay = match request.send().await {
+                Ok(res) if is_transient_status(res.status()) => {
+                    let delay = policy.delay_for(attempt, Some(&res));
+                    eprintln!(
+                        "request failed with {}, retrying in {:?}",
+                        res.status(),
+                        delay
+                    );
+                    delay
+                }
+                Err(err) if is_transient_error(&err) => {
+                    let delay = policy.delay_for(attempt, None);
+                    eprintln!("request failed: {}, retrying in {:?}", err, delay);
+                    delay
+                }
+                result => return result,
+            };
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::api::{ApiClient, Release, ReleaseStatus};
+use crate::edit_guard::EditGuard;
+use crate::output::{self, Committed, OutputFormat};
+use clap::{Args, Subcommand};
+
+#[derive(Args, Debug)]
+pub struct RolloutArgs {
+    /// Track holding the staged rollout
+    #[arg(short, long, default_value = "production", global = true)]
+    track: String,
+    #[command(subcommand)]
+    command: RolloutCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RolloutCommand {
+    /// Roll the release out to a larger fraction of users
+    Increase {
+        /// New fraction of users (e.g. 0.2)
+        user_fraction: f64,
+        /// Allow lowering the fraction
+        #[arg(long)]
+        force: bool,
+    },
+    /// Stop rolling out the release
+    Halt,
+    /// Continue a halted rollout, optionally at a new fraction
+    Resume {
+        #[arg(long)]
+        user_fraction: Option<f64>,
+    },
+    /// Roll the release out to all users
+    Complete,
+}
+
+pub async fn run(client: &ApiClient, args: RolloutArgs, format: OutputFormat) -> eyre::Result<()> {
+    let edit = EditGuard::open(client).await?;
+    let track = edit
+        .run(async {
+            let track = client.get_track(edit.id(), &args.track).await?;
+            let mut releases = track.releases.unwrap_or_default();
+            let staged = releases
+                .iter()
+                .position(|release| {
+                    matches!(
+                        release.status,
+                        Some(ReleaseStatus::InProgress | ReleaseStatus::Halted)
+                    )
+                })
+                .ok_or_else(|| eyre::eyre!("track {} has no staged rollout", args.track))?;
+            apply(&mut releases[staged], &args.command)?;
+            if matches!(args.command, RolloutCommand::Complete) {
+                releases = drop_other_completed(releases, staged);
+            }
+            client.update_track(edit.id(), &args.track, releases).await
+        })
+        .await?;
+    let committed = edit.commit().await?.is_committed();
+
+    output::print(
+        format,
+        &Committed {
+            committed,
+            result: track,
+        },
+    )
+}
+
+fn apply(release: &mut Release, command: &RolloutCommand) -> eyre::Result<()> {
+    let status = release.status.unwrap_or(ReleaseStatus::StatusUnspecified);
+    match command {
+        RolloutCommand::Increase {
+            user_fraction,
+            force,
+        } => {
+            if status != ReleaseStatus::InProgress {
+                eyre::bail!("the rollout is halted, resume it instead");
+            }
+            let current = release.user_fraction.unwrap_or_default();
+            if *user_fraction < current && !force {
+                eyre::bail!(
+                    "refusing to lower the user fraction from {} to {}, pass --force to do it anyway",
+                    current,
+                    user_fraction
+                );
+            }
+            release.user_fraction = Some(*user_fraction);
+        }
+        RolloutCommand::Halt => {
+            if status == ReleaseStatus::Halted {
+                eyre::bail!("the rollout is already halted");
+            }
+            release.status = Some(ReleaseStatus::Halted);
+        }
+        RolloutCommand::Resume { user_fraction } => {
+            if status != ReleaseStatus::Halted {
+                eyre::bail!("the rollout is not halted");
+            }
+            release.status = Some(ReleaseStatus::InProgress);
+            if user_fraction.is_some() {
+                release.user_fraction = *user_fraction;
+            }
+        }
+        RolloutCommand::Complete => {
+            release.status = Some(ReleaseStatus::Completed);
+            release.user_fraction = None;
+        }
+    }
+    release.validate()
+}
+
+/// Removes the completed releases other than the one at `kept`, as a track can only hold one
+/// completed release. Drafts queued on the track stay.
+fn drop_other_completed(releases: Vec<Release>, kept: usize) -> Vec<Release> {
+    releases
+        .into_iter()
+        .enumerate()
+        .filter(|(i, release)| *i == kept || release.status != Some(ReleaseStatus::Completed))
+        .map(|(_, release)| release)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(status: ReleaseStatus, user_fraction: Option<f64>) -> Release {
+        Release {
+            status: Some(status),
+            user_fraction,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn increase_refuses_to_lower_without_force() {
+        let mut staged = release(ReleaseStatus::InProgress, Some(0.2));
+        let lower = RolloutCommand::Increase {
+            user_fraction: 0.1,
+            force: false,
+        };
+        assert!(apply(&mut staged, &lower).is_err());
+        assert_eq!(staged.user_fraction, Some(0.2));
+
+        let forced = RolloutCommand::Increase {
+            user_fraction: 0.1,
+            force: true,
+        };
+        apply(&mut staged, &forced).unwrap();
+        assert_eq!(staged.user_fraction, Some(0.1));
+    }
+
+    #[test]
+    fn increase_requires_a_running_rollout() {
+        let mut staged = release(ReleaseStatus::Halted, Some(0.2));
+        let increase = RolloutCommand::Increase {
+            user_fraction: 0.5,
+            force: false,
+        };
+        assert!(apply(&mut staged, &increase).is_err());
+    }
+
+    #[test]
+    fn halt_and_resume() {
+        let mut staged = release(ReleaseStatus::InProgress, Some(0.2));
+        apply(&mut staged, &RolloutCommand::Halt).unwrap();
+        assert_eq!(staged.status, Some(ReleaseStatus::Halted));
+        assert!(apply(&mut staged, &RolloutCommand::Halt).is_err());
+
+        let resume = RolloutCommand::Resume {
+            user_fraction: Some(0.3),
+        };
+        apply(&mut staged, &resume).unwrap();
+        assert_eq!(staged.status, Some(ReleaseStatus::InProgress));
+        assert_eq!(staged.user_fraction, Some(0.3));
+        assert!(apply(&mut staged, &resume).is_err());
+    }
+
+    #[test]
+    fn complete_clears_the_user_fraction() {
+        let mut staged = release(ReleaseStatus::Halted, Some(0.2));
+        apply(&mut staged, &RolloutCommand::Complete).unwrap();
+        assert_eq!(staged.status, Some(ReleaseStatus::Completed));
+        assert_eq!(staged.user_fraction, None);
+    }
+
+    #[test]
+    fn completing_keeps_drafts() {
+        let named = |name: &str, status| Release {
+            name: Some(name.to_owned()),
+            status: Some(status),
+            ..Default::default()
+        };
+        let releases = vec![
+            named("old", ReleaseStatus::Completed),
+            named("staged", ReleaseStatus::Completed),
+            named("next", ReleaseStatus::Draft),
+        ];
+        let names: Vec<_> = drop_other_completed(releases, 1)
+            .into_iter()
+            .filter_map(|release| release.name)
+            .collect();
+        assert_eq!(names, ["staged", "next"]);
+    }
+}
@@ -0,0 +1,599 @@
+use crate::api::{ApiClient, AppDetails, Image, ImageType, Listing, LocalizedText, Release};
+use crate::details;
+use crate::edit_guard::EditGuard;
+use crate::images::{self, LocalImage};
+use crate::listings;
+use crate::output::{self, cell, Changes, Committed, OutputFormat, Tabular};
+use crate::release_notes;
+use clap::Args;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const DETAILS_FILE: &str = "details.json";
+pub const IMAGES_DIR: &str = "images";
+pub const CHANGELOGS_DIR: &str = "changelogs";
+
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg"];
+
+#[derive(Args, Debug)]
+pub struct SyncArgs {
+    /// Metadata directory in fastlane supply layout (<lang>/title.txt, <lang>/images/..)
+    dir: PathBuf,
+}
+
+/// Metadata read from a fastlane `supply` style directory. Anything missing locally is left
+/// untouched on the server.
+pub struct Metadata {
+    pub details: Option<AppDetails>,
+    pub listings: Vec<Listing>,
+    pub images: Vec<(String, ImageType, Vec<LocalImage>)>,
+    /// Release notes keyed by version code.
+    pub changelogs: BTreeMap<String, Vec<LocalizedText>>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Change {
+    #[serde(rename_all = "camelCase")]
+    Details {
+        field: &'static str,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Listing {
+        language: String,
+        field: &'static str,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    UploadImage {
+        language: String,
+        image_type: ImageType,
+        file: PathBuf,
+        sha256: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    DeleteImage {
+        language: String,
+        image_type: ImageType,
+        id: String,
+        sha256: String,
+    },
+    /// Screenshots are replaced as a whole when their order differs, as the API can only append.
+    #[serde(rename_all = "camelCase")]
+    DeleteAllImages {
+        language: String,
+        image_type: ImageType,
+        ids: Vec<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ReleaseNotes {
+        track: String,
+        version_code: String,
+        language: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+}
+
+/// The differences between local metadata and an edit, along with the requests resolving them.
+#[derive(Default)]
+struct Plan {
+    changes: Vec<Change>,
+    details: Option<AppDetails>,
+    listings: Vec<Listing>,
+    image_delete_alls: Vec<(String, ImageType)>,
+    image_deletes: Vec<(String, ImageType, String)>,
+    image_uploads: Vec<(String, ImageType, LocalImage)>,
+    tracks: Vec<(String, Vec<Release>)>,
+}
+
+pub async fn run(client: &ApiClient, args: SyncArgs, format: OutputFormat) -> eyre::Result<()> {
+    let metadata = read_metadata(&args.dir)?;
+    let edit = EditGuard::open(client).await?;
+    let changes = edit
+        .run(async {
+            let plan = plan(client, edit.id(), metadata).await?;
+            apply(client, edit.id(), &plan).await?;
+            Ok(plan.changes)
+        })
+        .await?;
+    let committed = if changes.is_empty() {
+        eprintln!("metadata is up to date");
+        edit.discard().await?;
+        false
+    } else {
+        edit.commit().await?.is_committed()
+    };
+    output::print(
+        format,
+        &Committed {
+            committed,
+            result: Changes { changes },
+        },
+    )
+}
+
+pub fn read_metadata(dir: &Path) -> eyre::Result<Metadata> {
+    let details_file = dir.join(DETAILS_FILE);
+    let details = if details_file.is_file() {
+        Some(details::read_file(&details_file)?)
+    } else {
+        None
+    };
+
+    let listings = listings::read_dir(dir)?;
+    for listing in &listings {
+        listings::validate(listing)?;
+    }
+
+    let mut images = Vec::new();
+    let mut changelogs: BTreeMap<String, Vec<LocalizedText>> = BTreeMap::new();
+    for language in language_dirs(dir)? {
+        let language_dir = dir.join(&language);
+        let images_dir = language_dir.join(IMAGES_DIR);
+        for image_type in ImageType::ALL {
+            if let Some(files) = image_files(&images_dir, image_type)? {
+                images.push((language.clone(), image_type, images::read_images(&files)?));
+            }
+        }
+
+        let changelogs_dir = language_dir.join(CHANGELOGS_DIR);
+        if !changelogs_dir.is_dir() {
+            continue;
+        }
+        for entry in std::fs::read_dir(&changelogs_dir)? {
+            let path = entry?.path();
+            let version_code = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(stem) if stem.parse::<i64>().is_ok() => stem.to_owned(),
+                _ => continue,
+            };
+            let note = LocalizedText {
+                language: language.clone(),
+                text: std::fs::read_to_string(&path)?.trim().to_owned(),
+            };
+            release_notes::validate(&note)?;
+            changelogs.entry(version_code).or_default().push(note);
+        }
+    }
+
+    Ok(Metadata {
+        details,
+        listings,
+        images,
+        changelogs,
+    })
+}
+
+fn language_dirs(dir: &Path) -> eyre::Result<Vec<String>> {
+    let mut languages = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            languages.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    languages.sort();
+    Ok(languages)
+}
+
+/// Finds the local files of an image type: `<type>.png` for single images, or the sorted
+/// contents of `<type>/` for screenshots. `None` means the type isn't managed locally.
+pub fn image_files(images_dir: &Path, image_type: ImageType) -> eyre::Result<Option<Vec<PathBuf>>> {
+    let name = image_type.to_string();
+    if image_type.is_single() {
+        return Ok(IMAGE_EXTENSIONS
+            .iter()
+            .map(|ext| images_dir.join(format!("{name}.{ext}")))
+            .find(|path| path.is_file())
+            .map(|path| vec![path]));
+    }
+    let dir = images_dir.join(name);
+    if !dir.is_dir() {
+        return Ok(None);
+    }
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        let is_image = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+        if is_image {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(Some(files))
+}
+
+async fn plan(client: &ApiClient, edit_id: &str, metadata: Metadata) -> eyre::Result<Plan> {
+    let mut plan = Plan::default();
+
+    if let Some(desired) = metadata.details {
+        let current = client.get_details(edit_id).await?;
+        let merged = details::merge(&current, &desired);
+        for (field, old, new) in details::diff(&current, &merged) {
+            plan.changes.push(Change::Details { field, old, new });
+        }
+        if merged != current {
+            plan.details = Some(merged);
+        }
+    }
+
+    let current_listings = client.list_listings(edit_id).await?.listings;
+    for desired in &metadata.listings {
+        let current = current_listings
+            .iter()
+            .find(|listing| listing.language == desired.language);
+        let field_changes = listings::diff(current, desired);
+        if field_changes.is_empty() {
+            continue;
+        }
+        plan.listings.push(listings::merge(current, desired));
+        plan.changes
+            .extend(field_changes.into_iter().map(|change| Change::Listing {
+                language: change.language,
+                field: change.field,
+                old: change.old,
+                new: change.new,
+            }));
+    }
+
+    for (language, image_type, local) in metadata.images {
+        let is_new_language = !current_listings
+            .iter()
+            .any(|listing| listing.language == language);
+        let existing = if is_new_language {
+            Vec::new()
+        } else {
+            client
+                .list_images(edit_id, &language, image_type)
+                .await?
+                .images
+        };
+        let diff = diff_images(&existing, &local, image_type);
+        if diff.delete_all {
+            plan.changes.push(Change::DeleteAllImages {
+                language: language.clone(),
+                image_type,
+                ids: existing.iter().map(|image| image.id.clone()).collect(),
+            });
+            plan.image_delete_alls.push((language.clone(), image_type));
+        }
+        for i in diff.deletes {
+            let image = &existing[i];
+            plan.changes.push(Change::DeleteImage {
+                language: language.clone(),
+                image_type,
+                id: image.id.clone(),
+                sha256: image.sha256.clone(),
+            });
+            plan.image_deletes
+                .push((language.clone(), image_type, image.id.clone()));
+        }
+        for (i, image) in local.into_iter().enumerate() {
+            if diff.uploads.contains(&i) {
+                plan.changes.push(Change::UploadImage {
+                    language: language.clone(),
+                    image_type,
+                    file: image.path.clone(),
+                    sha256: image.sha256.clone(),
+                });
+                plan.image_uploads
+                    .push((language.clone(), image_type, image));
+            }
+        }
+    }
+
+    if !metadata.changelogs.is_empty() {
+        for track in client.list_tracks(edit_id).await?.tracks {
+            let name = track.track.unwrap_or_default();
+            let mut releases = track.releases.unwrap_or_default();
+            let mut changed = false;
+            for release in &mut releases {
+                let version_code = match release.max_version_code() {
+                    Some(version_code) => version_code.to_string(),
+                    None => continue,
+                };
+                let notes = match metadata.changelogs.get(&version_code) {
+                    Some(notes) => notes,
+                    None => continue,
+                };
+                let release_notes = release.release_notes.get_or_insert_with(Vec::new);
+                for note in notes {
+                    let current = release_notes
+                        .iter_mut()
+                        .find(|current| current.language == note.language);
+                    let old = current.as_ref().map(|current| current.text.clone());
+                    if old.as_ref() == Some(&note.text) {
+                        continue;
+                    }
+                    match current {
+                        Some(current) => current.text = note.text.clone(),
+                        None => release_notes.push(note.clone()),
+                    }
+                    changed = true;
+                    plan.changes.push(Change::ReleaseNotes {
+                        track: name.clone(),
+                        version_code: version_code.clone(),
+                        language: note.language.clone(),
+                        old,
+                        new: Some(note.text.clone()),
+                    });
+                }
+            }
+            if changed {
+                plan.tracks.push((name, releases));
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+/// How to turn the images of one type on the server into the local ones, by index into
+/// `existing` and `local`.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ImageDiff {
+    delete_all: bool,
+    deletes: Vec<usize>,
+    uploads: Vec<usize>,
+}
+
+/// Deletes images missing locally and appends new ones. Uploads can only append, so when that
+/// wouldn't give the local order all screenshots are deleted and uploaded again in order.
+fn diff_images(existing: &[Image], local: &[LocalImage], image_type: ImageType) -> ImageDiff {
+    let deletes: Vec<usize> = (0..existing.len())
+        .filter(|&i| !local.iter().any(|image| image.sha256 == existing[i].sha256))
+        .collect();
+    let uploads: Vec<usize> = (0..local.len())
+        .filter(|&i| !existing.iter().any(|image| image.sha256 == local[i].sha256))
+        .collect();
+    let kept = (0..existing.len())
+        .filter(|i| !deletes.contains(i))
+        .map(|i| &existing[i].sha256);
+    let appended = uploads.iter().map(|&i| &local[i].sha256);
+    let in_order = kept
+        .chain(appended)
+        .eq(local.iter().map(|image| &image.sha256));
+    if in_order || image_type.is_single() {
+        ImageDiff {
+            delete_all: false,
+            deletes,
+            uploads,
+        }
+    } else {
+        ImageDiff {
+            delete_all: true,
+            deletes: Vec::new(),
+            uploads: (0..local.len()).collect(),
+        }
+    }
+}
+
+async fn apply(client: &ApiClient, edit_id: &str, plan: &Plan) -> eyre::Result<()> {
+    if let Some(details) = &plan.details {
+        client.update_details(edit_id, details).await?;
+    }
+    for listing in &plan.listings {
+        client.update_listing(edit_id, listing).await?;
+    }
+    for (language, image_type) in &plan.image_delete_alls {
+        client
+            .delete_all_images(edit_id, language, *image_type)
+            .await?;
+    }
+    for (language, image_type, id) in &plan.image_deletes {
+        client
+            .delete_image(edit_id, language, *image_type, id)
+            .await?;
+    }
+    for (language, image_type, image) in &plan.image_uploads {
+        client
+            .upload_image(
+                edit_id,
+                language,
+                *image_type,
+                image.content_type,
+                image.bytes.clone(),
+            )
+            .await?;
+    }
+    for (track, releases) in &plan.tracks {
+        client
+            .update_track(edit_id, track, releases.clone())
+            .await?;
+    }
+    Ok(())
+}
+
+impl Tabular for Vec<Change> {
+    fn headers(&self) -> &'static [&'static str] {
+        &["KIND", "TARGET", "ITEM", "ACTION"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.iter()
+            .map(|change| match change {
+                Change::Details { field, old, .. } => vec![
+                    "details".into(),
+                    "-".into(),
+                    field.to_string(),
+                    cell(Some(if old.is_some() { "changed" } else { "added" })),
+                ],
+                Change::Listing {
+                    language,
+                    field,
+                    old,
+                    ..
+                } => vec![
+                    "listing".into(),
+                    language.clone(),
+                    field.to_string(),
+                    cell(Some(if old.is_some() { "changed" } else { "added" })),
+                ],
+                Change::UploadImage {
+                    language,
+                    image_type,
+                    file,
+                    ..
+                } => vec![
+                    format!("image {image_type}"),
+                    language.clone(),
+                    file.display().to_string(),
+                    "upload".into(),
+                ],
+                Change::DeleteImage {
+                    language,
+                    image_type,
+                    id,
+                    ..
+                } => vec![
+                    format!("image {image_type}"),
+                    language.clone(),
+                    id.clone(),
+                    "delete".into(),
+                ],
+                Change::DeleteAllImages {
+                    language,
+                    image_type,
+                    ids,
+                } => vec![
+                    format!("image {image_type}"),
+                    language.clone(),
+                    format!("{} images", ids.len()),
+                    "delete all".into(),
+                ],
+                Change::ReleaseNotes {
+                    track,
+                    version_code,
+                    language,
+                    old,
+                    ..
+                } => vec![
+                    "release notes".into(),
+                    format!("{track} {version_code}"),
+                    language.clone(),
+                    cell(Some(if old.is_some() { "changed" } else { "added" })),
+                ],
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn existing(shas: &[&str]) -> Vec<Image> {
+        shas.iter()
+            .map(|sha256| Image {
+                id: format!("id-{sha256}"),
+                url: String::new(),
+                sha1: String::new(),
+                sha256: sha256.to_string(),
+            })
+            .collect()
+    }
+
+    fn local(shas: &[&str]) -> Vec<LocalImage> {
+        shas.iter()
+            .map(|sha256| LocalImage {
+                path: PathBuf::from(format!("{sha256}.png")),
+                content_type: "image/png",
+                sha256: sha256.to_string(),
+                bytes: Vec::new(),
+            })
+            .collect()
+    }
+
+    fn diff(existing_shas: &[&str], local_shas: &[&str], image_type: ImageType) -> ImageDiff {
+        diff_images(&existing(existing_shas), &local(local_shas), image_type)
+    }
+
+    #[test]
+    fn unchanged_images_need_nothing() {
+        let diff = diff(&["a", "b"], &["a", "b"], ImageType::PhoneScreenshots);
+        assert_eq!(diff, ImageDiff::default());
+    }
+
+    #[test]
+    fn reordered_screenshots_are_uploaded_again() {
+        let diff = diff(&["a", "b"], &["b", "a"], ImageType::PhoneScreenshots);
+        assert_eq!(
+            diff,
+            ImageDiff {
+                delete_all: true,
+                deletes: vec![],
+                uploads: vec![0, 1],
+            }
+        );
+    }
+
+    #[test]
+    fn replaced_screenshot_in_the_middle_keeps_its_position() {
+        let diff = diff(
+            &["a", "b", "c"],
+            &["a", "x", "c"],
+            ImageType::PhoneScreenshots,
+        );
+        assert!(diff.delete_all);
+        assert_eq!(diff.uploads, [0, 1, 2]);
+    }
+
+    #[test]
+    fn appended_screenshots_are_only_uploaded() {
+        let diff = diff(&["a"], &["a", "b"], ImageType::PhoneScreenshots);
+        assert_eq!(
+            diff,
+            ImageDiff {
+                delete_all: false,
+                deletes: vec![],
+                uploads: vec![1],
+            }
+        );
+    }
+
+    #[test]
+    fn removed_screenshots_are_only_deleted() {
+        let diff = diff(&["a", "b", "c"], &["a", "c"], ImageType::PhoneScreenshots);
+        assert_eq!(
+            diff,
+            ImageDiff {
+                delete_all: false,
+                deletes: vec![1],
+                uploads: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn replaced_single_image_is_deleted_and_uploaded() {
+        let diff = diff(&["a"], &["b"], ImageType::Icon);
+        assert_eq!(
+            diff,
+            ImageDiff {
+                delete_all: false,
+                deletes: vec![0],
+                uploads: vec![0],
+            }
+        );
+    }
+
+    #[test]
+    fn empty_local_dir_deletes_every_image() {
+        let diff = diff(&["a", "b"], &[], ImageType::PhoneScreenshots);
+        assert_eq!(
+            diff,
+            ImageDiff {
+                delete_all: false,
+                deletes: vec![0, 1],
+                uploads: vec![],
+            }
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use crate::api::{ApiClient, Testers};
+use crate::edit_guard::EditGuard;
+use crate::output::{self, Committed, OutputFormat, Tabular};
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum TestersCommand {
+    /// Show the Google Groups with access to a closed testing track
+    Get { track: String },
+    /// Replace the Google Groups with access to a closed testing track
+    Update {
+        track: String,
+        /// Email address of a Google Group, repeat for several; none removes all groups
+        #[arg(long = "google-group")]
+        google_groups: Vec<String>,
+    },
+}
+
+pub async fn run(
+    client: &ApiClient,
+    command: TestersCommand,
+    format: OutputFormat,
+) -> eyre::Result<()> {
+    match command {
+        TestersCommand::Get { track } => {
+            let edit = EditGuard::open(client).await?;
+            let testers = edit.run(client.get_testers(edit.id(), &track)).await?;
+            edit.discard().await?;
+            output::print(format, &testers)
+        }
+        TestersCommand::Update {
+            track,
+            google_groups,
+        } => {
+            let edit = EditGuard::open(client).await?;
+            let testers = edit
+                .run(client.update_testers(edit.id(), &track, &Testers { google_groups }))
+                .await?;
+            let committed = edit.commit().await?.is_committed();
+            output::print(
+                format,
+                &Committed {
+                    committed,
+                    result: testers,
+                },
+            )
+        }
+    }
+}
+
+impl Tabular for Testers {
+    fn headers(&self) -> &'static [&'static str] {
+        &["GOOGLE GROUP"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.google_groups
+            .iter()
+            .map(|group| vec![group.clone()])
+            .collect()
+    }
+}
@@ -0,0 +1,79 @@
+use crate::api::{ApiClient, Track};
+use crate::edit_guard::EditGuard;
+use crate::output::{self, cell, OutputFormat, Tabular};
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum TracksCommand {
+    /// List all tracks and their releases
+    List,
+    /// Show the releases of a single track
+    Get { track: String },
+}
+
+pub async fn run(
+    client: &ApiClient,
+    command: TracksCommand,
+    format: OutputFormat,
+) -> eyre::Result<()> {
+    let edit = EditGuard::open(client).await?;
+    let tracks = edit
+        .run(async {
+            match command {
+                TracksCommand::List => Ok(client.list_tracks(edit.id()).await?.tracks),
+                TracksCommand::Get { track } => {
+                    Ok(vec![client.get_track(edit.id(), &track).await?])
+                }
+            }
+        })
+        .await?;
+    edit.discard().await?;
+
+    output::print(format, &tracks)
+}
+
+const TRACK_HEADERS: &[&str] = &[
+    "TRACK",
+    "RELEASE",
+    "STATUS",
+    "VERSION CODES",
+    "USER FRACTION",
+];
+
+impl Tabular for Track {
+    fn headers(&self) -> &'static [&'static str] {
+        TRACK_HEADERS
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        let name = cell(self.track.as_ref());
+        let releases = self.releases.as_deref().unwrap_or_default();
+        if releases.is_empty() {
+            let mut row = vec!["-".to_owned(); TRACK_HEADERS.len()];
+            row[0] = name;
+            return vec![row];
+        }
+        releases
+            .iter()
+            .map(|release| {
+                vec![
+                    name.clone(),
+                    cell(release.name.as_ref()),
+                    cell(release.status),
+                    cell(release.version_codes.as_ref().map(|codes| codes.join(","))),
+                    cell(release.user_fraction),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl Tabular for Vec<Track> {
+    fn headers(&self) -> &'static [&'static str] {
+        TRACK_HEADERS
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.iter().flat_map(Track::rows).collect()
+    }
+}
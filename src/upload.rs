@@ -0,0 +1,323 @@
+use crate::api::{
+    ApiClient, DeobfuscationFileType, ExpansionFile, ExpansionFileType, Release, ReleaseStatus,
+    Testers,
+};
+use crate::edit_guard::EditGuard;
+use crate::output::{self, OutputFormat, Tabular};
+use crate::release_notes;
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Debug)]
+pub struct UploadArgs {
+    /// Paths to the .aab bundles or .apks to upload into the same release
+    #[arg(short, long, visible_alias = "apk", required = true, num_args = 1..)]
+    bundle: Vec<PathBuf>,
+    /// Upload as this artifact type instead of guessing from the file extensions
+    #[arg(long, value_enum)]
+    artifact_type: Option<ArtifactType>,
+    /// Override the version codes reported by the uploads
+    #[arg(short, long, num_args = 1..)]
+    version_code: Vec<String>,
+    /// Track to release to, e.g. internal, alpha, beta, production or a closed testing track
+    #[arg(short, long, default_value = "internal")]
+    track: String,
+    /// Status of the created release
+    #[arg(long, value_enum, default_value = "draft")]
+    status: ReleaseStatus,
+    /// Fraction of users to roll out to, only valid for inProgress releases (e.g. 0.05)
+    #[arg(long)]
+    user_fraction: Option<f64>,
+    /// Release notes for a language, read from a file (e.g. en-US=notes.txt)
+    #[arg(long, value_parser = release_notes::parse_release_notes_arg)]
+    release_notes: Vec<(String, PathBuf)>,
+    /// Directory of whatsnew-<lang> files or <lang>/<track>.txt notes
+    #[arg(long)]
+    release_notes_dir: Option<PathBuf>,
+    /// ProGuard/R8 mapping.txt, either one for all artifacts or one per artifact
+    #[arg(long)]
+    mapping: Vec<PathBuf>,
+    /// Zip of native debug symbols, either one for all artifacts or one per artifact
+    #[arg(long)]
+    native_symbols: Vec<PathBuf>,
+    /// Main expansion (OBB) file of an APK, either one for all APKs or one per APK
+    #[arg(long, conflicts_with = "obb_main_references")]
+    obb_main: Vec<PathBuf>,
+    /// Patch expansion (OBB) file of an APK, either one for all APKs or one per APK
+    #[arg(long, conflicts_with = "obb_patch_references")]
+    obb_patch: Vec<PathBuf>,
+    /// Reuse the main expansion file of a previously uploaded version code
+    #[arg(long)]
+    obb_main_references: Option<i64>,
+    /// Reuse the patch expansion file of a previously uploaded version code
+    #[arg(long)]
+    obb_patch_references: Option<i64>,
+    /// Google Group to give access to the track, repeat for several; replaces the current groups
+    #[arg(long = "testers-google-group")]
+    testers_google_groups: Vec<String>,
+    /// Validate the edit instead of committing it, then delete it
+    #[arg(long, visible_alias = "validate")]
+    dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArtifactType {
+    Aab,
+    Apk,
+}
+
+impl ArtifactType {
+    fn from_path(path: &Path) -> eyre::Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("aab") => Ok(Self::Aab),
+            Some(ext) if ext.eq_ignore_ascii_case("apk") => Ok(Self::Apk),
+            _ => eyre::bail!(
+                "cannot guess artifact type of {}, pass --artifact-type",
+                path.display()
+            ),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadResult {
+    edit_id: String,
+    track: String,
+    committed: bool,
+    artifacts: Vec<UploadedArtifact>,
+    release: Release,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadedArtifact {
+    path: PathBuf,
+    artifact_type: ArtifactType,
+    version_code: i64,
+    sha256: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mapping: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    native_symbols: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    obb_main: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    obb_patch: Option<PathBuf>,
+}
+
+impl Tabular for UploadResult {
+    fn headers(&self) -> &'static [&'static str] {
+        &["EDIT", "TRACK", "FILE", "VERSION CODE", "SHA256"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.artifacts
+            .iter()
+            .map(|artifact| {
+                vec![
+                    self.edit_id.clone(),
+                    self.track.clone(),
+                    artifact.path.display().to_string(),
+                    artifact.version_code.to_string(),
+                    artifact.sha256.clone(),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Matches files given for an option to the uploaded artifacts: a single file is used for
+/// every artifact, otherwise there has to be one file per artifact.
+fn per_artifact<'a>(
+    files: &'a [PathBuf],
+    artifacts: usize,
+    option: &str,
+) -> eyre::Result<Vec<Option<&'a PathBuf>>> {
+    match files.len() {
+        0 => Ok(vec![None; artifacts]),
+        1 => Ok(vec![files.first(); artifacts]),
+        count if count == artifacts => Ok(files.iter().map(Some).collect()),
+        count => eyre::bail!(
+            "got {} {} files for {} artifacts, pass either one or one per artifact",
+            count,
+            option,
+            artifacts
+        ),
+    }
+}
+
+pub async fn run(client: &ApiClient, args: UploadArgs, format: OutputFormat) -> eyre::Result<()> {
+    let mut release = Release {
+        status: Some(args.status),
+        user_fraction: args.user_fraction,
+        ..Default::default()
+    };
+    release.validate()?;
+    let notes = release_notes::load(
+        &args.release_notes,
+        args.release_notes_dir.as_deref(),
+        &args.track,
+    )?;
+    if !notes.is_empty() {
+        release.release_notes = Some(notes);
+    }
+    let mappings = per_artifact(&args.mapping, args.bundle.len(), "--mapping")?;
+    let native_symbols = per_artifact(&args.native_symbols, args.bundle.len(), "--native-symbols")?;
+    let obb_mains = per_artifact(&args.obb_main, args.bundle.len(), "--obb-main")?;
+    let obb_patches = per_artifact(&args.obb_patch, args.bundle.len(), "--obb-patch")?;
+    let mut expansion_references = Vec::new();
+    if let Some(version_code) = args.obb_main_references {
+        expansion_references.push((ExpansionFileType::Main, version_code));
+    }
+    if let Some(version_code) = args.obb_patch_references {
+        expansion_references.push((ExpansionFileType::Patch, version_code));
+    }
+    let mut artifacts = Vec::with_capacity(args.bundle.len());
+    for (i, path) in args.bundle.iter().enumerate() {
+        let artifact_type = match args.artifact_type {
+            Some(artifact_type) => artifact_type,
+            None => ArtifactType::from_path(path)?,
+        };
+        let (mapping, native_symbols) = (mappings[i], native_symbols[i]);
+        let mut deobfuscation_files = Vec::new();
+        if let Some(mapping) = mapping {
+            deobfuscation_files.push((
+                DeobfuscationFileType::Proguard,
+                mapping,
+                tokio::fs::File::open(mapping).await?,
+            ));
+        }
+        if let Some(native_symbols) = native_symbols {
+            deobfuscation_files.push((
+                DeobfuscationFileType::NativeCode,
+                native_symbols,
+                tokio::fs::File::open(native_symbols).await?,
+            ));
+        }
+        let mut expansion_files = Vec::new();
+        for (file_type, obb) in [
+            (ExpansionFileType::Main, obb_mains[i]),
+            (ExpansionFileType::Patch, obb_patches[i]),
+        ] {
+            if let Some(obb) = obb {
+                expansion_files.push((file_type, obb, tokio::fs::File::open(obb).await?));
+            }
+        }
+        if artifact_type != ArtifactType::Apk
+            && !(expansion_files.is_empty() && expansion_references.is_empty())
+        {
+            eyre::bail!(
+                "expansion files can only be added to APKs, not {}",
+                path.display()
+            );
+        }
+        artifacts.push((
+            path,
+            artifact_type,
+            tokio::fs::File::open(path).await?,
+            deobfuscation_files,
+            expansion_files,
+        ));
+    }
+
+    let edit = EditGuard::open(client).await?;
+    let edit_id = edit.id().to_owned();
+    let (uploaded, release) = edit
+        .run(async {
+            let mut uploaded = Vec::with_capacity(artifacts.len());
+            for (path, artifact_type, artifact, deobfuscation_files, expansion_files) in artifacts {
+                let (version_code, sha256) = match artifact_type {
+                    ArtifactType::Aab => {
+                        let bundle = client.upload_bundle(edit.id(), artifact).await?;
+                        (bundle.version_code, bundle.sha256)
+                    }
+                    ArtifactType::Apk => {
+                        let apk = client.upload_apk(edit.id(), artifact).await?;
+                        (apk.version_code, apk.binary.sha256)
+                    }
+                };
+                let mut artifact = UploadedArtifact {
+                    path: path.clone(),
+                    artifact_type,
+                    version_code,
+                    sha256,
+                    mapping: None,
+                    native_symbols: None,
+                    obb_main: None,
+                    obb_patch: None,
+                };
+                for (file_type, path, file) in deobfuscation_files {
+                    client
+                        .upload_deobfuscation_file(edit.id(), version_code, file_type, file)
+                        .await?;
+                    match file_type {
+                        DeobfuscationFileType::Proguard => artifact.mapping = Some(path.clone()),
+                        DeobfuscationFileType::NativeCode => {
+                            artifact.native_symbols = Some(path.clone())
+                        }
+                    }
+                }
+                for (file_type, path, file) in expansion_files {
+                    client
+                        .upload_expansion_file(edit.id(), version_code, file_type, file)
+                        .await?;
+                    match file_type {
+                        ExpansionFileType::Main => artifact.obb_main = Some(path.clone()),
+                        ExpansionFileType::Patch => artifact.obb_patch = Some(path.clone()),
+                    }
+                }
+                for &(file_type, references_version) in &expansion_references {
+                    let expansion_file = ExpansionFile {
+                        references_version: Some(references_version),
+                        ..Default::default()
+                    };
+                    client
+                        .patch_expansion_file(edit.id(), version_code, file_type, &expansion_file)
+                        .await?;
+                }
+                uploaded.push(artifact);
+            }
+            release.version_codes = Some(if args.version_code.is_empty() {
+                uploaded
+                    .iter()
+                    .map(|artifact| artifact.version_code.to_string())
+                    .collect()
+            } else {
+                args.version_code
+            });
+            client
+                .update_track(edit.id(), &args.track, vec![release.clone()])
+                .await?;
+            if !args.testers_google_groups.is_empty() {
+                let testers = Testers {
+                    google_groups: args.testers_google_groups,
+                };
+                client
+                    .update_testers(edit.id(), &args.track, &testers)
+                    .await?;
+            }
+            Ok((uploaded, release))
+        })
+        .await?;
+    let committed = if args.dry_run {
+        edit.validate().await?;
+        eprintln!("edit {edit_id} is valid");
+        false
+    } else {
+        edit.commit().await?.is_committed()
+    };
+
+    output::print(
+        format,
+        &UploadResult {
+            edit_id,
+            track: args.track,
+            committed,
+            artifacts: uploaded,
+            release,
+        },
+    )
+}